@@ -0,0 +1,30 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{File, Id, Task},
+};
+
+/*
+task: The `task_id` is assigned by the list.
+index: Optional, task is appended if not provided.
+ */
+#[tauri::command]
+pub fn add_task(
+    state: State<HeldState>,
+    id: String,
+    task: Task,
+    index: Option<usize>,
+) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let file = state
+        .edit_todo_list(&Id::from_string(id), |todo_list| {
+            todo_list.add_task(task, index).map(|_| ())
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(file)
+}
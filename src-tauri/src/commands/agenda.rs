@@ -0,0 +1,30 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{Agenda, AgendaFilter},
+};
+
+const DEFAULT_AGENDA_DAYS: u32 = 7;
+
+/*
+Open tasks of all todo lists and notes grouped into overdue, today, upcoming and no date.
+days: Optional, how many days after today count as upcoming, 7 if not provided.
+project: Optional, only tasks of the project.
+priority: Optional, only tasks with the priority.
+ */
+#[tauri::command]
+pub fn agenda(
+    state: State<HeldState>,
+    days: Option<u32>,
+    project: Option<String>,
+    priority: Option<u8>,
+) -> Result<Agenda, String> {
+    let state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let filter = AgendaFilter { project, priority };
+    Ok(state.agenda(days.unwrap_or(DEFAULT_AGENDA_DAYS), &filter))
+}
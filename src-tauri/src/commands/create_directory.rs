@@ -0,0 +1,24 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{Directory, Id},
+};
+
+#[tauri::command]
+pub fn create_directory(
+    state: State<HeldState>,
+    parent_id: String,
+    name: String,
+) -> Result<Directory, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let parent_id = Id::from_string(parent_id);
+    let dir = state
+        .create_dir(&parent_id, &name)
+        .map_err(|e| e.to_string())?;
+    Ok(dir)
+}
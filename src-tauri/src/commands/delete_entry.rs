@@ -10,7 +10,7 @@ pub fn delete_entry(state: State<HeldState>, entry_id: String) -> Result<(), Str
     };
 
     let entry_id = Id::from_string(entry_id);
-    state.delete_file(&entry_id);
+    state.delete_file(&entry_id).map_err(|e| e.to_string())?;
 
     Ok(())
 }
@@ -0,0 +1,23 @@
+use std::path::PathBuf;
+
+use tauri::State;
+
+use crate::{state::HeldState, types::Id};
+
+/*
+Writes todo lists as tasks of an iCalendar file.
+path: Path of the ics file, picked in a save dialog by the frontend.
+id: Optional, the todo list to export, every todo list in the vault if not provided.
+ */
+#[tauri::command]
+pub fn export_ics(state: State<HeldState>, path: String, id: Option<String>) -> Result<(), String> {
+    let state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let id = id.map(Id::from_string);
+    state
+        .export_ics(&PathBuf::from(path), id.as_ref())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{Id, TaskFormat},
+};
+
+/*
+Writes a todo list as a todo.txt file or as a markdown checklist.
+path: Path of the file, picked in a save dialog by the frontend.
+ */
+#[tauri::command]
+pub fn export_tasks(
+    state: State<HeldState>,
+    path: String,
+    id: String,
+    format: TaskFormat,
+) -> Result<(), String> {
+    let state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    state
+        .export_tasks(&PathBuf::from(path), &Id::from_string(id), format)
+        .map_err(|e| e.to_string())
+}
@@ -9,5 +9,6 @@ pub fn get_graph(state: State<HeldState>) -> Result<Graph, String> {
         Err(_) => return Err("Could not lock state".to_string()),
     };
 
-    unimplemented!()
+    let graph = state.get_graph().map_err(|e| e.to_string())?;
+    Ok(graph)
 }
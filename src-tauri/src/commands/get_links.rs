@@ -0,0 +1,19 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{Id, NoteLinks},
+};
+
+#[tauri::command]
+pub fn get_links(state: State<HeldState>, id: String) -> Result<NoteLinks, String> {
+    let state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let links = state
+        .get_links(&Id::from_string(id))
+        .map_err(|e| e.to_string())?;
+    Ok(links)
+}
@@ -0,0 +1,21 @@
+use tauri::State;
+
+use crate::{state::HeldState, types::FilePreview};
+
+/*
+Lists files with the tag, files with tags nested in it (`tag/child`) are included.
+prefix: Optional, if true, every tag starting with `tag` matches.
+ */
+#[tauri::command]
+pub fn get_tagged_files(
+    state: State<HeldState>,
+    tag: String,
+    prefix: Option<bool>,
+) -> Result<Vec<FilePreview>, String> {
+    let state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    Ok(state.get_tagged_files(&tag, prefix.unwrap_or(false)))
+}
@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{File, Id},
+};
+
+/*
+Creates a todo list from the tasks and events of an iCalendar file.
+path: Path of the ics file.
+parent_id: Optional, the directory of the new todo list, the notes dir if not provided.
+ */
+#[tauri::command]
+pub fn import_ics(
+    state: State<HeldState>,
+    path: String,
+    parent_id: Option<String>,
+) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let parent_id = Id::from_string(parent_id.unwrap_or_default());
+    state
+        .import_ics(&PathBuf::from(path), &parent_id)
+        .map_err(|e| e.to_string())
+}
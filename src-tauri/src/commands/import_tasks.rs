@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{File, Id, TaskFormat},
+};
+
+/*
+Creates a todo list from a todo.txt file or from the checklist items of a markdown file.
+path: Path of the file.
+parent_id: Optional, the directory of the new todo list, the notes dir if not provided.
+ */
+#[tauri::command]
+pub fn import_tasks(
+    state: State<HeldState>,
+    path: String,
+    parent_id: Option<String>,
+    format: TaskFormat,
+) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let parent_id = Id::from_string(parent_id.unwrap_or_default());
+    state
+        .import_tasks(&PathBuf::from(path), &parent_id, format)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,29 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{File, Id},
+};
+
+/*
+index: Optional, column is appended if not provided.
+ */
+#[tauri::command]
+pub fn insert_table_column(
+    state: State<HeldState>,
+    id: String,
+    index: Option<usize>,
+    name: String,
+) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let file = state
+        .edit_table(&Id::from_string(id), |table| {
+            table.insert_column(index, name)
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(file)
+}
@@ -0,0 +1,26 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{File, Id},
+};
+
+/*
+index: Optional, row is appended if not provided.
+ */
+#[tauri::command]
+pub fn insert_table_row(
+    state: State<HeldState>,
+    id: String,
+    index: Option<usize>,
+) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let file = state
+        .edit_table(&Id::from_string(id), |table| table.insert_row(index))
+        .map_err(|e| e.to_string())?;
+    Ok(file)
+}
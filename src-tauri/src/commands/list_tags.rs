@@ -0,0 +1,13 @@
+use tauri::State;
+
+use crate::{state::HeldState, types::TagCount};
+
+#[tauri::command]
+pub fn list_tags(state: State<HeldState>) -> Result<Vec<TagCount>, String> {
+    let state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    Ok(state.list_tags())
+}
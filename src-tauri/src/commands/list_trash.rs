@@ -0,0 +1,14 @@
+use tauri::State;
+
+use crate::{state::HeldState, types::TrashEntry};
+
+#[tauri::command]
+pub fn list_trash(state: State<HeldState>) -> Result<Vec<TrashEntry>, String> {
+    let state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let entries = state.list_trash().map_err(|e| e.to_string())?;
+    Ok(entries)
+}
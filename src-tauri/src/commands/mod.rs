@@ -1,19 +1,93 @@
+mod add_task;
+mod agenda;
+mod create_directory;
 mod create_file;
 mod delete_entry;
 mod delete_file;
+mod delete_table_column;
+mod delete_table_row;
+mod delete_task;
+mod empty_trash;
+mod export_ics;
+mod export_tasks;
 mod get_directory;
 mod get_file;
+mod get_links;
+mod get_tagged_files;
+mod import_ics;
+mod import_tasks;
+mod indent_task;
+mod insert_table_column;
+mod insert_table_row;
+mod list_tags;
+mod list_trash;
+mod move_entry;
+mod move_subtree;
+mod move_table_column;
+mod move_task;
+mod outdent_task;
+mod preview_link_rewrite;
 mod put_file;
+mod quick_add_task;
 mod refresh;
+mod rename_entry;
+mod rename_table_column;
+mod rename_tag;
+mod rename_with_links;
+mod request_thumbnails;
+mod restore_from_trash;
+mod search;
+mod set_table_cell;
+mod set_task_completed;
+mod toggle_inline_task;
+mod undo_link_rewrite;
+mod update_task;
 mod get_graph;
 mod welcome_startup;
 
 pub use get_graph::*;
+pub use add_task::*;
+pub use agenda::*;
+pub use create_directory::*;
 pub use create_file::*;
 pub use delete_entry::*;
 pub use delete_file::*;
+pub use delete_table_column::*;
+pub use delete_table_row::*;
+pub use delete_task::*;
+pub use empty_trash::*;
+pub use export_ics::*;
+pub use export_tasks::*;
 pub use get_directory::*;
 pub use get_file::*;
+pub use get_links::*;
+pub use get_tagged_files::*;
+pub use import_ics::*;
+pub use import_tasks::*;
+pub use indent_task::*;
+pub use insert_table_column::*;
+pub use insert_table_row::*;
+pub use list_tags::*;
+pub use list_trash::*;
+pub use move_entry::*;
+pub use move_subtree::*;
+pub use move_table_column::*;
+pub use move_task::*;
+pub use outdent_task::*;
+pub use preview_link_rewrite::*;
 pub use put_file::*;
+pub use quick_add_task::*;
 pub use refresh::*;
+pub use rename_entry::*;
+pub use rename_table_column::*;
+pub use rename_tag::*;
+pub use rename_with_links::*;
+pub use request_thumbnails::*;
+pub use restore_from_trash::*;
+pub use search::*;
+pub use set_table_cell::*;
+pub use set_task_completed::*;
+pub use toggle_inline_task::*;
+pub use undo_link_rewrite::*;
+pub use update_task::*;
 pub use welcome_startup::*;
@@ -0,0 +1,25 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{Id, MovedEntry},
+};
+
+#[tauri::command]
+pub fn move_entry(
+    state: State<HeldState>,
+    entry_id: String,
+    parent_id: String,
+) -> Result<MovedEntry, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let entry_id = Id::from_string(entry_id);
+    let parent_id = Id::from_string(parent_id);
+    let moved = state
+        .move_entry(&entry_id, &parent_id)
+        .map_err(|e| e.to_string())?;
+    Ok(moved)
+}
@@ -0,0 +1,32 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{File, Id},
+};
+
+/*
+Moves the task together with its subtasks, the task ids stay the same.
+parent_id: Optional, the new parent, the task goes to the top level if not provided.
+index: Optional, position among the subtasks of the new parent, the task is appended if not provided.
+ */
+#[tauri::command]
+pub fn move_subtree(
+    state: State<HeldState>,
+    id: String,
+    task_id: u32,
+    parent_id: Option<u32>,
+    index: Option<usize>,
+) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let file = state
+        .edit_todo_list(&Id::from_string(id), |todo_list| {
+            todo_list.move_subtree(task_id, parent_id, index)
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(file)
+}
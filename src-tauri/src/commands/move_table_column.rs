@@ -0,0 +1,24 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{File, Id},
+};
+
+#[tauri::command]
+pub fn move_table_column(
+    state: State<HeldState>,
+    id: String,
+    from: usize,
+    to: usize,
+) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let file = state
+        .edit_table(&Id::from_string(id), |table| table.move_column(from, to))
+        .map_err(|e| e.to_string())?;
+    Ok(file)
+}
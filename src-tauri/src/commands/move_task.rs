@@ -0,0 +1,26 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{File, Id},
+};
+
+#[tauri::command]
+pub fn move_task(
+    state: State<HeldState>,
+    id: String,
+    task_id: u32,
+    index: usize,
+) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let file = state
+        .edit_todo_list(&Id::from_string(id), |todo_list| {
+            todo_list.move_task(task_id, index)
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(file)
+}
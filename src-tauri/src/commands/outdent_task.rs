@@ -0,0 +1,24 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{File, Id},
+};
+
+/*
+Moves the task, with its subtasks, out of its parent to right after it.
+ */
+#[tauri::command]
+pub fn outdent_task(state: State<HeldState>, id: String, task_id: u32) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let file = state
+        .edit_todo_list(&Id::from_string(id), |todo_list| {
+            todo_list.outdent_task(task_id)
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(file)
+}
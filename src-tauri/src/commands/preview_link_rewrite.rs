@@ -0,0 +1,31 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{Id, LinkRewrite},
+};
+
+/*
+Lists links that would be rewritten by `rename_with_links`, nothing is changed.
+name: Optional, new name of the entry.
+parent_id: Optional, directory the entry would be moved to.
+ */
+#[tauri::command]
+pub fn preview_link_rewrite(
+    state: State<HeldState>,
+    entry_id: String,
+    name: Option<String>,
+    parent_id: Option<String>,
+) -> Result<LinkRewrite, String> {
+    let state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let entry_id = Id::from_string(entry_id);
+    let parent_id = parent_id.map(Id::from_string);
+    let rewrite = state
+        .plan_link_rewrite(&entry_id, name.as_deref(), parent_id.as_ref())
+        .map_err(|e| e.to_string())?;
+    Ok(rewrite)
+}
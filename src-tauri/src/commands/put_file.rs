@@ -2,14 +2,16 @@ use tauri::State;
 
 use crate::{
     state::HeldState,
-    types::{File, FileContent, Id},
+    types::{File, FileContent, Frontmatter, Id},
 };
 
 /*
 Put command for creating or updating a file.
 id: Required, is file exists, it will be updated, otherwise new file will be created.
-name: Optional, if provided, file will be renamed.
-content: Optional, if provided, file content will be updated.
+name: Optional, if provided, file will be renamed and links pointing to it rewritten.
+content: Optional, if provided, file content will be updated. Notes keep their frontmatter when
+the content comes without one, as the editor only works with the body.
+properties: Optional, if provided, frontmatter properties of the note will be replaced.
 file_type: Required, if file does not exist, it will be created with this file type.
  */
 #[tauri::command]
@@ -18,31 +20,58 @@ pub fn put_file(
     id: String,
     name: Option<String>,
     content: Option<String>,
+    properties: Option<serde_yaml::Mapping>,
 ) -> Result<File, String> {
     let mut state = match state.lock() {
         Ok(state) => state,
         Err(_) => return Err("Could not lock state".to_string()),
     };
 
-    let file_manager = state.get_file_manager();
     let mut id = Id::from_string(id);
 
-    match file_manager.exists(&id) {
+    match state.get_file_manager().exists(&id) {
         true => {
             if let Some(name) = name {
-                id = file_manager.rename(&id, &name).map_err(|e| e.to_string())?;
+                let (moved, _) = state
+                    .relocate_with_links(&id, Some(&name), None)
+                    .map_err(|e| e.to_string())?;
+                id = moved.id;
             }
 
-            let mut file = file_manager.read(&id).map_err(|e| e.to_string())?;
+            let mut file = state.get_file(&id).map_err(|e| e.to_string())?;
 
-            if let Some(content) = content {
-                let file_content =
-                    FileContent::custom_deserialize(&content.as_bytes(), file.content.get_type())
-                        .map_err(|e| e.to_string())?;
-                file = state
-                    .update_file(&id, file_content)
-                    .map_err(|e| e.to_string())?;
+            if content.is_none() && properties.is_none() {
+                return Ok(file);
+            }
+
+            let frontmatter = match &file.content {
+                FileContent::Note(note) => note.get_frontmatter().cloned(),
+                _ => None,
+            };
+            let mut file_content = match content {
+                Some(content) => {
+                    FileContent::custom_deserialize(content.as_bytes(), file.content.get_type())
+                        .map_err(|e| e.to_string())?
+                }
+                None => file.content,
+            };
+
+            if let FileContent::Note(note) = &mut file_content {
+                let mut frontmatter = note.get_frontmatter().cloned().or(frontmatter);
+                if let Some(properties) = properties {
+                    frontmatter = Some(Frontmatter {
+                        properties,
+                        ..frontmatter.unwrap_or_default()
+                    });
+                }
+                note.set_frontmatter(frontmatter);
+            } else if properties.is_some() {
+                return Err("Only notes have properties".to_string());
             }
+
+            file = state
+                .update_file(&id, file_content)
+                .map_err(|e| e.to_string())?;
             Ok(file)
         }
         false => Err("File does not exist".to_string()),
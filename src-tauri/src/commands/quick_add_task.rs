@@ -0,0 +1,31 @@
+use chrono::Local;
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{parse_quick_add, File, Id},
+};
+
+/*
+Appends a task written like `Pay invoice tomorrow 5pm !2 +finance every month` to the todo list.
+text: Dates, times, priority, project, tags and repeat are read from it, the rest is the content.
+ */
+#[tauri::command]
+pub fn quick_add_task(state: State<HeldState>, id: String, text: String) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let task = parse_quick_add(&text, &Local::now());
+    if task.get_content().is_empty() {
+        return Err("Task has no content".to_string());
+    }
+
+    let file = state
+        .edit_todo_list(&Id::from_string(id), |todo_list| {
+            todo_list.add_task(task, None).map(|_| ())
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(file)
+}
@@ -0,0 +1,24 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{Id, MovedEntry},
+};
+
+#[tauri::command]
+pub fn rename_entry(
+    state: State<HeldState>,
+    entry_id: String,
+    name: String,
+) -> Result<MovedEntry, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let entry_id = Id::from_string(entry_id);
+    let moved = state
+        .rename_entry(&entry_id, &name)
+        .map_err(|e| e.to_string())?;
+    Ok(moved)
+}
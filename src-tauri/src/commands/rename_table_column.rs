@@ -0,0 +1,26 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{File, Id},
+};
+
+#[tauri::command]
+pub fn rename_table_column(
+    state: State<HeldState>,
+    id: String,
+    index: usize,
+    name: String,
+) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let file = state
+        .edit_table(&Id::from_string(id), |table| {
+            table.rename_column(index, name)
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(file)
+}
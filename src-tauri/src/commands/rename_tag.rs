@@ -0,0 +1,24 @@
+use tauri::State;
+
+use crate::{state::HeldState, types::FilePreview};
+
+/*
+Renames a tag across the vault, renaming to an existing tag merges them.
+Returns previews of the changed files.
+ */
+#[tauri::command]
+pub fn rename_tag(
+    state: State<HeldState>,
+    tag: String,
+    new_tag: String,
+) -> Result<Vec<FilePreview>, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let files = state
+        .rename_tag(&tag, &new_tag)
+        .map_err(|e| e.to_string())?;
+    Ok(files)
+}
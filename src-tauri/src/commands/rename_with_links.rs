@@ -0,0 +1,31 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{Id, LinkRewrite, MovedEntry},
+};
+
+/*
+Renames and/or moves an entry and rewrites every link pointing into it.
+name: Optional, if provided, entry will be renamed.
+parent_id: Optional, if provided, entry will be moved to this directory.
+ */
+#[tauri::command]
+pub fn rename_with_links(
+    state: State<HeldState>,
+    entry_id: String,
+    name: Option<String>,
+    parent_id: Option<String>,
+) -> Result<(MovedEntry, LinkRewrite), String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let entry_id = Id::from_string(entry_id);
+    let parent_id = parent_id.map(Id::from_string);
+    let result = state
+        .relocate_with_links(&entry_id, name.as_deref(), parent_id.as_ref())
+        .map_err(|e| e.to_string())?;
+    Ok(result)
+}
@@ -0,0 +1,24 @@
+use tauri::State;
+
+use crate::{state::ThumbnailWorker, types::Id};
+
+/*
+Returns the ids whose thumbnails are ready to be loaded from `noter://vault/<id>?thumbnail`.
+The others are generated in the background and announced with a `thumbnail-created` event.
+ids: Png, jpeg, gif or webp images, svg images and missing files have no thumbnails and are
+left out.
+ */
+#[tauri::command]
+pub fn request_thumbnails(
+    worker: State<ThumbnailWorker>,
+    ids: Vec<String>,
+) -> Result<Vec<Id>, String> {
+    let mut ready = Vec::new();
+    for id in ids {
+        let id = Id::from_string(id);
+        if worker.request(&id).map_err(|e| e.to_string())? {
+            ready.push(id);
+        }
+    }
+    Ok(ready)
+}
@@ -0,0 +1,19 @@
+use tauri::State;
+
+use crate::{state::HeldState, types::FilePreview};
+
+#[tauri::command]
+pub fn restore_from_trash(
+    state: State<HeldState>,
+    trash_id: String,
+) -> Result<Vec<FilePreview>, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let files = state
+        .restore_from_trash(&trash_id)
+        .map_err(|e| e.to_string())?;
+    Ok(files)
+}
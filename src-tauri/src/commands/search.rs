@@ -0,0 +1,20 @@
+use tauri::State;
+
+use crate::{state::HeldState, types::SearchResult};
+
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+#[tauri::command]
+pub fn search(
+    state: State<HeldState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SearchResult>, String> {
+    let state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let results = state.search(&query, limit.unwrap_or(DEFAULT_SEARCH_LIMIT));
+    Ok(results)
+}
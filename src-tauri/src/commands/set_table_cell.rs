@@ -0,0 +1,27 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{File, Id},
+};
+
+#[tauri::command]
+pub fn set_table_cell(
+    state: State<HeldState>,
+    id: String,
+    row: usize,
+    column: usize,
+    value: String,
+) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let file = state
+        .edit_table(&Id::from_string(id), |table| {
+            table.set_cell(row, column, value)
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(file)
+}
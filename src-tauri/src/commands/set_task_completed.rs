@@ -0,0 +1,30 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{File, Id},
+};
+
+/*
+cascade: Optional, if true, subtasks are completed or uncompleted along with the task.
+ */
+#[tauri::command]
+pub fn set_task_completed(
+    state: State<HeldState>,
+    id: String,
+    task_id: u32,
+    completed: bool,
+    cascade: Option<bool>,
+) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let file = state
+        .edit_todo_list(&Id::from_string(id), |todo_list| {
+            todo_list.set_completed(task_id, completed, cascade.unwrap_or(false))
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(file)
+}
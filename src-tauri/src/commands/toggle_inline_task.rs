@@ -0,0 +1,26 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{File, Id},
+};
+
+/*
+Checks or unchecks a `- [ ]` task written in a note, only its line is rewritten.
+line: 1-based line of the note body, as given by the agenda.
+ */
+#[tauri::command]
+pub fn toggle_inline_task(
+    state: State<HeldState>,
+    id: String,
+    line: usize,
+) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    state
+        .toggle_inline_task(&Id::from_string(id), line)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,14 @@
+use tauri::State;
+
+use crate::{state::HeldState, types::MovedEntry};
+
+#[tauri::command]
+pub fn undo_link_rewrite(state: State<HeldState>) -> Result<MovedEntry, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let moved = state.undo_link_rewrite().map_err(|e| e.to_string())?;
+    Ok(moved)
+}
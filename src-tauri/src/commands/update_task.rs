@@ -0,0 +1,21 @@
+use tauri::State;
+
+use crate::{
+    state::HeldState,
+    types::{File, Id, Task},
+};
+
+#[tauri::command]
+pub fn update_task(state: State<HeldState>, id: String, task: Task) -> Result<File, String> {
+    let mut state = match state.lock() {
+        Ok(state) => state,
+        Err(_) => return Err("Could not lock state".to_string()),
+    };
+
+    let file = state
+        .edit_todo_list(&Id::from_string(id), |todo_list| {
+            todo_list.update_task(task)
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(file)
+}
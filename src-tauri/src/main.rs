@@ -1,6 +1,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod commands;
+mod protocol;
 mod state;
 mod types;
 mod utils;
@@ -9,6 +10,7 @@ use std::fs::create_dir_all;
 
 use crate::{
     commands::*,
+    protocol::VAULT_PROTOCOL,
     types::Config,
     utils::{run_startup, run_welcome},
 };
@@ -31,6 +33,7 @@ fn main() {
 
             Ok(())
         })
+        .register_uri_scheme_protocol(VAULT_PROTOCOL, protocol::handle)
         .invoke_handler(tauri::generate_handler![
             refresh,
             create_file,
@@ -40,7 +43,44 @@ fn main() {
             get_directory,
             welcome_startup,
             delete_entry,
-            get_graph
+            get_graph,
+            search,
+            list_trash,
+            restore_from_trash,
+            empty_trash,
+            create_directory,
+            rename_entry,
+            move_entry,
+            preview_link_rewrite,
+            rename_with_links,
+            undo_link_rewrite,
+            get_links,
+            list_tags,
+            get_tagged_files,
+            rename_tag,
+            set_table_cell,
+            insert_table_row,
+            delete_table_row,
+            insert_table_column,
+            rename_table_column,
+            delete_table_column,
+            move_table_column,
+            request_thumbnails,
+            add_task,
+            update_task,
+            set_task_completed,
+            delete_task,
+            move_task,
+            agenda,
+            export_ics,
+            import_ics,
+            export_tasks,
+            import_tasks,
+            toggle_inline_task,
+            quick_add_task,
+            indent_task,
+            outdent_task,
+            move_subtree
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,218 @@
+use std::{
+    error::Error,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use percent_encoding::percent_decode_str;
+use tauri::{
+    http::{
+        header::{
+            ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_SECURITY_POLICY, CONTENT_TYPE,
+            RANGE, X_CONTENT_TYPE_OPTIONS,
+        },
+        status::StatusCode,
+        HttpRange, Request, Response, ResponseBuilder,
+    },
+    AppHandle, Manager,
+};
+
+use crate::{
+    state::{HeldState, ThumbnailWorker},
+    types::Id,
+};
+
+/// Files of the vault are served as `noter://vault/<id>`, on Windows the webview uses
+/// `https://noter.localhost/<id>` instead, `convertFileSrc(id, "noter")` builds the right one
+pub const VAULT_PROTOCOL: &str = "noter";
+
+/// Largest part of a file sent for one range request, players ask for the rest as they go
+const MAX_RANGE_LENGTH: u64 = 1024 * 1024;
+
+/// Largest file sent whole to requests without a range, larger ones are refused instead of
+/// being read into memory at once. Players ask for ranges, so this only limits images, pdfs
+/// and the like.
+const MAX_BODY_LENGTH: u64 = 128 * 1024 * 1024;
+
+pub fn get_mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "md" => "text/markdown; charset=utf-8",
+        "csv" => "text/csv; charset=utf-8",
+        "txt" => "text/plain; charset=utf-8",
+        "json" => "application/json",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Takes the id out of the request uri, the host part is ignored
+pub fn get_id(uri: &str) -> Result<Id> {
+    let (_, rest) = uri
+        .split_once("://")
+        .ok_or_else(|| anyhow!("Invalid uri"))?;
+    let (_, path) = rest.split_once('/').ok_or_else(|| anyhow!("Invalid uri"))?;
+    let path = path.split(['?', '#']).next().unwrap_or("");
+
+    let id = percent_decode_str(path).decode_utf8()?;
+    Ok(Id::from_string(id.trim_matches('/').to_string()))
+}
+
+/// `noter://vault/<id>?thumbnail` serves the cached thumbnail of an image instead of the image
+pub fn is_thumbnail(uri: &str) -> bool {
+    uri.split_once('?')
+        .map(|(_, query)| query.split(['&', '#']).any(|param| param == "thumbnail"))
+        .unwrap_or(false)
+}
+
+/// Path of the file with given id, ids leading out of the notes dir, including through
+/// symlinks, and hidden entries are rejected
+pub fn resolve_path(notes_dir: &Path, id: &Id) -> Result<PathBuf> {
+    let escapes = id
+        .as_str()
+        .split(['/', '\\'])
+        .any(|part| part.is_empty() || part.starts_with('.'));
+    if escapes {
+        return Err(anyhow!("Invalid id"));
+    }
+
+    let root = notes_dir.canonicalize()?;
+    let path = root.join(id.as_str()).canonicalize()?;
+    if !path.starts_with(&root) || !path.is_file() {
+        return Err(anyhow!("File is not in notes dir"));
+    }
+    Ok(path)
+}
+
+fn empty(status: StatusCode) -> Result<Response, Box<dyn Error>> {
+    ResponseBuilder::new().status(status).body(Vec::new())
+}
+
+pub fn handle(app: &AppHandle, request: &Request) -> Result<Response, Box<dyn Error>> {
+    let notes_dir = match app.try_state::<HeldState>() {
+        Some(state) => match state.lock() {
+            Ok(state) => state.get_notes_dir().clone(),
+            Err(_) => return empty(StatusCode::SERVICE_UNAVAILABLE),
+        },
+        None => return empty(StatusCode::SERVICE_UNAVAILABLE),
+    };
+
+    let uri = request.uri();
+    let (path, id) = match get_id(uri).and_then(|id| Ok((resolve_path(&notes_dir, &id)?, id))) {
+        Ok(found) => found,
+        Err(_) => return empty(StatusCode::NOT_FOUND),
+    };
+
+    let path = match is_thumbnail(uri) {
+        true => match app
+            .try_state::<ThumbnailWorker>()
+            .and_then(|worker| worker.get(&id))
+        {
+            Some(thumbnail) => thumbnail,
+            None => return empty(StatusCode::NOT_FOUND),
+        },
+        false => path,
+    };
+
+    let mut file = File::open(&path)?;
+    let len = file.metadata()?.len();
+    let mime_type = get_mime_type(&path);
+
+    let mut response = ResponseBuilder::new()
+        .header(CONTENT_TYPE, mime_type)
+        .header(ACCEPT_RANGES, "bytes")
+        .header(X_CONTENT_TYPE_OPTIONS, "nosniff");
+    if mime_type == "image/svg+xml" {
+        // svg can carry scripts, it is only ever shown as an image
+        response = response.header(
+            CONTENT_SECURITY_POLICY,
+            "default-src 'none'; style-src 'unsafe-inline'",
+        );
+    }
+
+    let range = match request.headers().get(RANGE) {
+        Some(range) => range.to_str()?,
+        None if len <= MAX_BODY_LENGTH => {
+            let mut body = Vec::with_capacity(len as usize);
+            file.read_to_end(&mut body)?;
+            return response.header(CONTENT_LENGTH, len).body(body);
+        }
+        None => return empty(StatusCode::PAYLOAD_TOO_LARGE),
+    };
+
+    // only the first range is served when more are asked for, which browsers handle
+    let (start, end) = match HttpRange::parse(range, len).ok().as_deref() {
+        Some([range, ..]) if range.length > 0 => (
+            range.start,
+            range.start + (range.length - 1).min(MAX_RANGE_LENGTH - 1),
+        ),
+        _ => {
+            return ResponseBuilder::new()
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(CONTENT_RANGE, format!("bytes */{}", len))
+                .body(Vec::new())
+        }
+    };
+
+    let mut body = Vec::with_capacity((end + 1 - start) as usize);
+    file.seek(SeekFrom::Start(start))?;
+    file.take(end + 1 - start).read_to_end(&mut body)?;
+
+    response
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len))
+        .header(CONTENT_LENGTH, end + 1 - start)
+        .body(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_get_id() {
+        let id = get_id("noter://vault/img%2Fmy%20cat.png?t=1").unwrap();
+        assert_eq!(id.as_str(), "img/my cat.png");
+
+        let id = get_id("https://noter.localhost/notes/a.md").unwrap();
+        assert_eq!(id.as_str(), "notes/a.md");
+
+        assert!(is_thumbnail("noter://vault/cat.png?thumbnail"));
+        assert!(!is_thumbnail("noter://vault/thumbnail.png"));
+    }
+
+    #[test]
+    fn test_resolve_path() {
+        let dir = std::env::temp_dir().join(format!("noter-protocol-{}", std::process::id()));
+        let notes_dir = dir.join("vault");
+        fs::create_dir_all(notes_dir.join("img")).unwrap();
+        fs::write(notes_dir.join("img/cat.png"), []).unwrap();
+        fs::write(dir.join("secret.txt"), []).unwrap();
+
+        let id = |id: &str| Id::from_string(id.to_string());
+        assert!(resolve_path(&notes_dir, &id("img/cat.png")).is_ok());
+        assert!(resolve_path(&notes_dir, &id("img")).is_err());
+        assert!(resolve_path(&notes_dir, &id("../secret.txt")).is_err());
+        assert!(resolve_path(&notes_dir, &id("img/../../secret.txt")).is_err());
+        assert!(resolve_path(&notes_dir, &id(dir.join("secret.txt").to_str().unwrap())).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
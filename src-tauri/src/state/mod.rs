@@ -1,3 +1,9 @@
 mod noter_state;
+mod reminders;
+mod thumbnails;
+mod watcher;
 
 pub use noter_state::*;
+pub use reminders::*;
+pub use thumbnails::*;
+pub use watcher::*;
@@ -1,24 +1,166 @@
 use crate::types::{
-    DataAdapter, Directory, Entry, File, FileContent, FileManager, FilePreview, Id, SortOptions,
+    export_todo_lists, import_todo_list, toggle_inline_task, Agenda, AgendaFilter, CachedFile,
+    DataAdapter, Directory, Entry, File, FileContent, FileManager, FilePreview, FileStamp,
+    FileType, Graph, Id, IndexCache, LinkIndex, LinkRewrite, MovedEntry, Note, NoteLinks, Reminder,
+    SearchIndex, SearchResult, SortOptions, Table, TagCount, TagIndex, TaskFormat, TaskIndex,
+    TodoList, TrashEntry,
 };
 use anyhow::{anyhow, Result};
-use chrono::Utc;
-use std::{path::PathBuf, sync::Mutex};
+use chrono::{Local, Utc};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
 
 pub struct NoterState {
     file_manager: FileManager,
     data_adapter: DataAdapter,
+    search_index: SearchIndex,
+    link_index: LinkIndex,
+    tag_index: TagIndex,
+    task_index: TaskIndex,
+    /// fs size and mtime of every indexed file, used to tell which cached files are stale
+    stamps: HashMap<Id, FileStamp>,
+    /// applied link rewrites, the last one can be undone
+    link_rewrites: Vec<LinkRewrite>,
 }
 
+const MAX_UNDO_LINK_REWRITES: usize = 20;
+
 impl NoterState {
     pub fn new(notes_dir: PathBuf) -> Result<Self> {
-        let file_manager = FileManager::new(notes_dir.clone());
-        let data_adapter = DataAdapter::new();
+        let cache = IndexCache::load(&notes_dir);
+        let mut state = Self {
+            file_manager: FileManager::new(notes_dir),
+            data_adapter: DataAdapter::new(),
+            search_index: SearchIndex::new(),
+            link_index: LinkIndex::new(),
+            tag_index: TagIndex::new(),
+            task_index: TaskIndex::new(),
+            stamps: HashMap::new(),
+            link_rewrites: Vec::new(),
+        };
 
-        Ok(Self {
-            file_manager,
-            data_adapter,
-        })
+        state.build_index(cache)?;
+        if let Err(e) = state.store_cache() {
+            println!("Could not store index cache; {:?}", e);
+        }
+        Ok(state)
+    }
+
+    /// Every change of a file goes through here, so all indexes stay in sync with the fs
+    fn index(&mut self, file: &File) {
+        self.data_adapter.insert_file(file.get_preview());
+        self.search_index.insert_file(file);
+        self.link_index.insert_file(file);
+        self.tag_index.insert_file(file);
+        self.task_index.insert_file(file);
+        self.stamp(&file.id);
+    }
+
+    fn index_cached(&mut self, file: CachedFile) {
+        let id = file.preview.id.clone();
+        self.data_adapter.insert_file(file.preview);
+        self.search_index.insert_lines(&id, file.lines);
+        self.link_index.insert_links(&id, file.links);
+        self.tag_index.insert_tags(&id, file.tags);
+        self.task_index.insert_tasks(&id, file.tasks);
+        self.stamps.insert(id, file.stamp);
+    }
+
+    fn unindex(&mut self, id: &Id) -> Vec<Id> {
+        let removed = self.data_adapter.delete_entries_under(id);
+        removed.iter().for_each(|id| {
+            self.search_index.delete_file(id);
+            self.link_index.delete_file(id);
+            self.tag_index.delete_file(id);
+            self.task_index.delete_file(id);
+            self.stamps.remove(id);
+            self.invalidate_thumbnails(id);
+        });
+        removed
+    }
+
+    fn invalidate_thumbnails(&self, id: &Id) {
+        if let Err(e) = self.file_manager.get_thumbnail_cache().invalidate(id) {
+            println!("Could not remove thumbnails; {:?}; {:?}", e, id);
+        }
+    }
+
+    fn stamp(&mut self, id: &Id) {
+        let metadata = fs::metadata(self.file_manager.get_path(id));
+        match metadata.map(|m| FileStamp::from_metadata(&m)) {
+            Ok(Ok(stamp)) => self.stamps.insert(id.clone(), stamp),
+            _ => self.stamps.remove(id),
+        };
+    }
+
+    /// Fills the indexes from the notes dir, only files that changed since they were cached
+    /// are read and parsed again
+    fn build_index(&mut self, mut cache: HashMap<Id, CachedFile>) -> Result<()> {
+        let file_manager = self.file_manager.clone();
+        self.data_adapter.clear();
+        self.search_index = SearchIndex::new();
+        self.link_index = LinkIndex::new();
+        self.tag_index = TagIndex::new();
+        self.task_index = TaskIndex::new();
+        self.stamps.clear();
+
+        file_manager.scan_dir(file_manager.get_notes_dir(), &mut |id, metadata| {
+            let stamp = FileStamp::from_metadata(metadata).ok();
+
+            if let Some(cached) = cache.remove(&id) {
+                if Some(cached.stamp) == stamp {
+                    let preview = cached.preview.clone();
+                    self.index_cached(cached);
+                    return Some(preview);
+                }
+            }
+
+            id.get_type()?;
+            match file_manager.read(&id) {
+                Ok(file) => {
+                    self.index(&file);
+                    Some(file.get_preview())
+                }
+                Err(e) => {
+                    println!("Could not read file from fs; {:?}; {:?}", e, id);
+                    None
+                }
+            }
+        })?;
+        Ok(())
+    }
+
+    fn get_cached_files(&self) -> HashMap<Id, CachedFile> {
+        self.stamps
+            .iter()
+            .filter_map(|(id, stamp)| match self.data_adapter.get_file(id) {
+                Some(Entry::File(preview)) => Some((
+                    id.clone(),
+                    CachedFile {
+                        stamp: *stamp,
+                        preview: preview.clone(),
+                        lines: self
+                            .search_index
+                            .get_document(id)
+                            .cloned()
+                            .unwrap_or_default(),
+                        links: self.link_index.get_links(id).cloned().unwrap_or_default(),
+                        tags: self.tag_index.get_tags(id).cloned().unwrap_or_default(),
+                        tasks: self.task_index.get_tasks(id).cloned().unwrap_or_default(),
+                    },
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn store_cache(&self) -> Result<()> {
+        let files = self.get_cached_files().into_values().collect();
+        IndexCache::new(files).store(self.get_notes_dir())
     }
 
     pub fn get_notes_dir(&self) -> &PathBuf {
@@ -35,18 +177,330 @@ impl NoterState {
 
     pub fn create_file(&mut self, file: &File) -> Result<()> {
         self.file_manager.save(file)?;
-        self.data_adapter.insert_file(file.get_preview());
+        self.index(file);
         Ok(())
     }
 
     pub fn delete_file(&mut self, id: &Id) -> Result<()> {
         self.file_manager.delete(&id)?;
-        self.data_adapter.delete_file(&id);
+        self.unindex(id);
         Ok(())
     }
 
+    pub fn list_trash(&self) -> Result<Vec<TrashEntry>> {
+        self.file_manager.get_trash().list()
+    }
+
+    pub fn restore_from_trash(&mut self, trash_id: &str) -> Result<Vec<FilePreview>> {
+        let id = self.file_manager.restore(trash_id)?;
+        let path = self.file_manager.get_path(&id);
+
+        match path.is_dir() {
+            true => self.index_dir(&path),
+            false => Ok(vec![self.index_file(&id)?]),
+        }
+    }
+
+    pub fn empty_trash(&self) -> Result<Vec<TrashEntry>> {
+        self.file_manager.get_trash().empty()
+    }
+
+    pub fn purge_trash(&self, retention_days: u32) -> Result<Vec<TrashEntry>> {
+        self.file_manager
+            .get_trash()
+            .purge_older_than(chrono::Duration::days(retention_days.into()))
+    }
+
+    /// Moves index records of the entry and everything nested in it after it was renamed or
+    /// moved, returns pairs of old and new ids
+    fn move_indexed(&mut self, id: &Id, new_id: &Id) -> Vec<(Id, Id)> {
+        let mut moved = Vec::new();
+
+        for old_id in self.data_adapter.get_ids_under(id) {
+            let moved_id = old_id.replace_prefix(id, new_id);
+
+            if let Some(Entry::File(preview)) = self.data_adapter.get_file(&old_id).cloned() {
+                self.data_adapter.delete_file(&old_id);
+                self.data_adapter.insert_file(FilePreview {
+                    id: moved_id.clone(),
+                    name: moved_id.get_name(),
+                    ..preview
+                });
+            }
+            self.search_index.rename_file(&old_id, &moved_id);
+            self.link_index.rename_file(&old_id, &moved_id);
+            self.tag_index.rename_file(&old_id, &moved_id);
+            self.task_index.rename_file(&old_id, &moved_id);
+            self.stamps.remove(&old_id);
+            self.invalidate_thumbnails(&old_id);
+            self.stamp(&moved_id);
+
+            moved.push((old_id, moved_id));
+        }
+
+        moved
+    }
+
+    fn moved_entry(&mut self, id: &Id, new_id: Id) -> Result<MovedEntry> {
+        let moved = self.move_indexed(id, &new_id);
+        if moved.is_empty() && self.file_manager.get_path(&new_id).is_file() {
+            self.index_file(&new_id)?;
+        }
+
+        Ok(MovedEntry {
+            old_id: id.clone(),
+            entry: self.get_entry(&new_id)?,
+            id: new_id,
+            moved,
+        })
+    }
+
+    pub fn rename_entry(&mut self, id: &Id, name: &str) -> Result<MovedEntry> {
+        let new_id = self.file_manager.rename(id, name)?;
+        self.moved_entry(id, new_id)
+    }
+
+    pub fn move_entry(&mut self, id: &Id, parent_id: &Id) -> Result<MovedEntry> {
+        let new_id = self.file_manager.move_entry(id, parent_id)?;
+        self.moved_entry(id, new_id)
+    }
+
+    fn get_target_id(&self, id: &Id, name: Option<&str>, parent_id: Option<&Id>) -> Result<Id> {
+        let moved_id = match parent_id {
+            Some(parent_id) => self.file_manager.get_moved_id(id, parent_id)?,
+            None => id.clone(),
+        };
+
+        match name {
+            Some(name) => {
+                let renamed_id = self.file_manager.get_renamed_id(id, name)?;
+                let parent = moved_id
+                    .get_parent(self.get_notes_dir())
+                    .unwrap_or_else(|| Id::from_string(String::new()));
+                Ok(parent.join(renamed_id.get_file_name()))
+            }
+            None => Ok(moved_id),
+        }
+    }
+
+    fn read_note(&self, id: &Id) -> Result<Note> {
+        match self.file_manager.read(id)?.content {
+            FileContent::Note(note) => Ok(*note),
+            _ => Err(anyhow!("Not a note")),
+        }
+    }
+
+    fn read_note_body(&self, id: &Id) -> Result<String> {
+        Ok(self.read_note(id)?.get_body().clone())
+    }
+
+    /// Replaces the body of the note, frontmatter is kept as it is
+    fn write_note_body(&mut self, id: &Id, body: String) -> Result<String> {
+        let mut note = self.read_note(id)?;
+        let previous = note.get_body().clone();
+        note.set_body(body);
+        self.update_file(id, FileContent::Note(Box::new(note)))?;
+        Ok(previous)
+    }
+
+    /// Writes all bodies or none of them, already written notes are restored on failure
+    fn write_note_bodies(&mut self, bodies: Vec<(Id, String)>) -> Result<()> {
+        let mut written: Vec<(Id, String)> = Vec::new();
+
+        for (id, body) in bodies {
+            match self.write_note_body(&id, body) {
+                Ok(previous) => written.push((id, previous)),
+                Err(e) => {
+                    for (id, body) in written.into_iter().rev() {
+                        let _ = self.write_note_body(&id, body);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lists links across the vault that would change if the entry was renamed or moved
+    pub fn plan_link_rewrite(
+        &self,
+        id: &Id,
+        name: Option<&str>,
+        parent_id: Option<&Id>,
+    ) -> Result<LinkRewrite> {
+        let new_id = self.get_target_id(id, name, parent_id)?;
+        let moved: Vec<(Id, Id)> = self
+            .data_adapter
+            .get_ids_under(id)
+            .into_iter()
+            .map(|old_id| {
+                let moved_id = old_id.replace_prefix(id, &new_id);
+                (old_id, moved_id)
+            })
+            .collect();
+
+        // only notes linking into the moved entry have to be read
+        let mut sources: Vec<Id> = moved
+            .iter()
+            .flat_map(|(old_id, _)| self.link_index.get_backlinks(old_id))
+            .map(|backlink| backlink.source)
+            .chain(moved.iter().map(|(old_id, _)| old_id.clone()))
+            .filter(|id| self.link_index.get_links(id).is_some())
+            .collect();
+        sources.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        sources.dedup();
+
+        let notes = sources
+            .into_iter()
+            .filter_map(|id| match self.file_manager.read(&id) {
+                Ok(File {
+                    content: FileContent::Note(note),
+                    ..
+                }) => Some((id, *note)),
+                _ => None,
+            })
+            .collect();
+
+        Ok(LinkRewrite::plan(
+            id,
+            &new_id,
+            &moved,
+            notes,
+            self.link_index.get_resolver(),
+        ))
+    }
+
+    /// Renames or moves the entry and rewrites all links pointing into it
+    pub fn relocate_with_links(
+        &mut self,
+        id: &Id,
+        name: Option<&str>,
+        parent_id: Option<&Id>,
+    ) -> Result<(MovedEntry, LinkRewrite)> {
+        let rewrite = self.plan_link_rewrite(id, name, parent_id)?;
+        self.file_manager.relocate(id, &rewrite.new_id)?;
+        let moved = self.moved_entry(id, rewrite.new_id.clone())?;
+
+        let bodies = rewrite
+            .files
+            .iter()
+            .map(|f| (f.id.clone(), f.new_body.clone()))
+            .collect();
+        if let Err(e) = self.write_note_bodies(bodies) {
+            self.file_manager.relocate(&rewrite.new_id, id)?;
+            self.moved_entry(&rewrite.new_id, id.clone())?;
+            return Err(e);
+        }
+
+        self.link_rewrites.push(rewrite.clone());
+        if self.link_rewrites.len() > MAX_UNDO_LINK_REWRITES {
+            self.link_rewrites.remove(0);
+        }
+
+        Ok((moved, rewrite))
+    }
+
+    /// Reverts the last rename done with `relocate_with_links`, including all rewritten links
+    pub fn undo_link_rewrite(&mut self) -> Result<MovedEntry> {
+        let rewrite = match self.link_rewrites.last() {
+            Some(rewrite) => rewrite.clone(),
+            None => return Err(anyhow!("Nothing to undo")),
+        };
+
+        for file in &rewrite.files {
+            if self.read_note_body(&file.id)? != file.new_body {
+                return Err(anyhow!("{} was changed since, can not undo", file.id));
+            }
+        }
+
+        let bodies = rewrite
+            .files
+            .iter()
+            .map(|f| (f.id.clone(), f.old_body.clone()))
+            .collect();
+        self.write_note_bodies(bodies)?;
+
+        if let Err(e) = self.file_manager.relocate(&rewrite.new_id, &rewrite.old_id) {
+            let bodies = rewrite
+                .files
+                .iter()
+                .map(|f| (f.id.clone(), f.new_body.clone()))
+                .collect();
+            self.write_note_bodies(bodies)?;
+            return Err(e);
+        }
+
+        self.link_rewrites.pop();
+        self.moved_entry(&rewrite.new_id, rewrite.old_id.clone())
+    }
+
+    pub fn create_dir(&mut self, parent_id: &Id, name: &str) -> Result<Directory> {
+        let id = self.file_manager.create_dir(parent_id, name)?;
+        match self.get_entry(&id)? {
+            Entry::Directory(dir) => Ok(dir),
+            _ => Err(anyhow!("Not a directory")),
+        }
+    }
+
+    /// Re-reads a file that was changed outside of the app and updates the index
+    pub fn index_file(&mut self, id: &Id) -> Result<FilePreview> {
+        self.invalidate_thumbnails(id);
+        let file = self.file_manager.read(id)?;
+        self.index(&file);
+        Ok(file.get_preview())
+    }
+
+    /// Indexes every file in a directory that appeared in the notes dir
+    pub fn index_dir(&mut self, dir: &Path) -> Result<Vec<FilePreview>> {
+        let file_manager = self.file_manager.clone();
+        let mut files = Vec::new();
+
+        file_manager.read_dir(dir, &mut |file| {
+            self.index(file);
+            files.push(file.get_preview());
+        })?;
+        Ok(files)
+    }
+
+    pub fn is_indexed(&self, id: &Id) -> bool {
+        self.data_adapter.get_file(id).is_some()
+    }
+
+    /// Drops a file or a whole directory that disappeared from the filesystem from the index
+    pub fn unindex_entry(&mut self, id: &Id) -> Vec<Id> {
+        self.unindex(id)
+    }
+
+    pub fn reindex(&mut self) -> Result<()> {
+        let cache = self.get_cached_files();
+        self.build_index(cache)
+    }
+
+    /// Builds the directory tree from the index, so only the folder structure is read from fs
     pub fn get_directory(&self) -> Result<Directory> {
-        self.file_manager.read_notes_dir()
+        self.scan_dir(self.get_notes_dir())
+    }
+
+    fn scan_dir(&self, dir: &Path) -> Result<Directory> {
+        self.file_manager
+            .scan_dir(dir, &mut |id, _| match self.data_adapter.get_file(&id) {
+                Some(Entry::File(preview)) => Some(preview.clone()),
+                _ => None,
+            })
+    }
+
+    /// Returns the indexed file or the directory subtree with given id
+    pub fn get_entry(&self, id: &Id) -> Result<Entry> {
+        let path = self.file_manager.get_path(id);
+        if path.is_dir() {
+            return Ok(Entry::Directory(self.scan_dir(&path)?));
+        }
+
+        match self.data_adapter.get_file(id) {
+            Some(entry) => Ok(entry.clone()),
+            None => Err(anyhow!("File not found")),
+        }
     }
 
     pub fn get_file(&self, id: &Id) -> Result<File> {
@@ -63,16 +517,234 @@ impl NoterState {
             .get_file(id)
             .ok_or_else(|| anyhow!("File not found"))?;
 
-        match file_preview {
+        match file_preview.clone() {
             Entry::File(file) => {
                 let new_file = File::new(file.id.clone(), content, file.created_at, Utc::now());
                 self.file_manager.save(&new_file)?;
-                self.data_adapter.insert_file(new_file.get_preview());
+                self.index(&new_file);
                 Ok(new_file)
             }
             _ => return Err(anyhow!("Not a file")),
         }
     }
+
+    /// Applies an edit to a table file and saves it
+    pub fn edit_table<F>(&mut self, id: &Id, edit: F) -> Result<File>
+    where
+        F: FnOnce(&mut Table) -> Result<()>,
+    {
+        let mut table = match self.file_manager.read(id)?.content {
+            FileContent::Table(table) => table,
+            _ => return Err(anyhow!("Not a table")),
+        };
+        edit(&mut table)?;
+        self.update_file(id, FileContent::Table(table))
+    }
+
+    /// Applies an edit to a todo list and saves it
+    pub fn edit_todo_list<F>(&mut self, id: &Id, edit: F) -> Result<File>
+    where
+        F: FnOnce(&mut TodoList) -> Result<()>,
+    {
+        let mut todo_list = self.read_todo_list(id)?;
+        edit(&mut todo_list)?;
+        self.update_file(id, FileContent::TodoList(Box::new(todo_list)))
+    }
+
+    /// Checks or unchecks the task written on the line of the note body
+    pub fn toggle_inline_task(&mut self, id: &Id, line: usize) -> Result<File> {
+        let mut note = match self.file_manager.read(id)?.content {
+            FileContent::Note(note) => note,
+            _ => return Err(anyhow!("Not a note")),
+        };
+        let body = toggle_inline_task(note.get_body(), line, Utc::now())?;
+        note.set_body(body);
+        self.update_file(id, FileContent::Note(note))
+    }
+
+    fn read_todo_list(&self, id: &Id) -> Result<TodoList> {
+        match self.file_manager.read(id)?.content {
+            FileContent::TodoList(todo_list) => Ok(*todo_list),
+            _ => Err(anyhow!("Not a todo list")),
+        }
+    }
+
+    /// Writes the todo list, or every todo list in the vault without an id, to an ics file
+    pub fn export_ics(&self, path: &Path, id: Option<&Id>) -> Result<()> {
+        let mut ids: Vec<&Id> = match id {
+            Some(id) => vec![id],
+            None => self
+                .stamps
+                .keys()
+                .filter(|id| id.get_type() == Some(FileType::TodoList))
+                .collect(),
+        };
+        ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        let lists = ids
+            .into_iter()
+            .map(|id| Ok((id.clone(), self.read_todo_list(id)?)))
+            .collect::<Result<Vec<(Id, TodoList)>>>()?;
+        fs::write(path, export_todo_lists(&lists))?;
+        Ok(())
+    }
+
+    /// Reads the tasks and events of an ics file into a new todo list named after the file
+    pub fn import_ics(&mut self, path: &Path, parent_id: &Id) -> Result<File> {
+        let todo_list = import_todo_list(&fs::read_to_string(path)?)?;
+        self.create_imported_todo_list(path, parent_id, todo_list)
+    }
+
+    /// Writes the todo list as todo.txt or as a markdown checklist
+    pub fn export_tasks(&self, path: &Path, id: &Id, format: TaskFormat) -> Result<()> {
+        let todo_list = self.read_todo_list(id)?;
+        fs::write(path, format.export(&todo_list))?;
+        Ok(())
+    }
+
+    /// Reads a todo.txt file or the checklist of a markdown file into a new todo list named
+    /// after the file
+    pub fn import_tasks(
+        &mut self,
+        path: &Path,
+        parent_id: &Id,
+        format: TaskFormat,
+    ) -> Result<File> {
+        let todo_list = format.import(&fs::read_to_string(path)?);
+        self.create_imported_todo_list(path, parent_id, todo_list)
+    }
+
+    fn create_imported_todo_list(
+        &mut self,
+        path: &Path,
+        parent_id: &Id,
+        todo_list: TodoList,
+    ) -> Result<File> {
+        let name = path
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .filter(|name| !name.is_empty())
+            .unwrap_or("Imported tasks");
+
+        let id = Id::create_id(
+            name,
+            parent_id,
+            FileType::TodoList,
+            self.file_manager.get_notes_dir(),
+        );
+        let id = self.file_manager.get_free_id(&id);
+        let time = Utc::now();
+        let file = File::new(id, FileContent::TodoList(Box::new(todo_list)), time, time);
+        self.create_file(&file)?;
+        Ok(file)
+    }
+
+    pub fn get_graph(&self) -> Result<Graph> {
+        let mut graph = Graph::new();
+        self.get_all_files(None)
+            .into_iter()
+            .for_each(|file| graph.add_node(file));
+
+        for (from, to) in self.link_index.get_edges() {
+            graph.add_edge(from, to);
+        }
+
+        Ok(graph)
+    }
+
+    /// Outgoing links of the note and links pointing to it from the rest of the vault
+    pub fn get_links(&self, id: &Id) -> Result<NoteLinks> {
+        if !self.is_indexed(id) {
+            return Err(anyhow!("File not found"));
+        }
+
+        Ok(NoteLinks {
+            id: id.clone(),
+            outgoing: self.link_index.get_outgoing(id),
+            backlinks: self.link_index.get_backlinks(id),
+        })
+    }
+
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        self.search_index
+            .search(query)
+            .into_iter()
+            .filter_map(
+                |(id, score, matches)| match self.data_adapter.get_file(&id) {
+                    Some(Entry::File(file)) => Some(SearchResult {
+                        file: file.clone(),
+                        score,
+                        matches,
+                    }),
+                    _ => None,
+                },
+            )
+            .take(limit)
+            .collect()
+    }
+
+    pub fn list_tags(&self) -> Vec<TagCount> {
+        self.tag_index.list()
+    }
+
+    pub fn get_tagged_files(&self, tag: &str, prefix: bool) -> Vec<FilePreview> {
+        self.tag_index
+            .get_files(tag, prefix)
+            .into_iter()
+            .filter_map(|id| match self.data_adapter.get_file(&id) {
+                Some(Entry::File(file)) => Some(file.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Renames the tag and all tags nested in it in every file, renaming to a tag that is
+    /// already used merges the two. Returns the files that were changed. Every file is read
+    /// before any is written, and the written ones are restored when a later one fails.
+    pub fn rename_tag(&mut self, tag: &str, new_tag: &str) -> Result<Vec<FilePreview>> {
+        let old = TagIndex::normalize(tag).ok_or_else(|| anyhow!("Invalid tag"))?;
+        let new = TagIndex::clean(new_tag)
+            .ok_or_else(|| anyhow!("Invalid tag"))?
+            .to_string();
+
+        let mut renamed = Vec::new();
+        for id in self.tag_index.get_files(&old, false) {
+            let mut content = self.file_manager.read(&id)?.content;
+            if TagIndex::rename_in_content(&mut content, &old, &new) {
+                let previous = self.file_manager.read(&id)?.content;
+                renamed.push((id, previous, content));
+            }
+        }
+
+        let mut changed = Vec::new();
+        let mut written: Vec<(Id, FileContent)> = Vec::new();
+        for (id, previous, content) in renamed {
+            match self.update_file(&id, content) {
+                Ok(file) => {
+                    changed.push(file.get_preview());
+                    written.push((id, previous));
+                }
+                Err(e) => {
+                    for (id, previous) in written.into_iter().rev() {
+                        let _ = self.update_file(&id, previous);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Open tasks of every todo list and note due before the end of the next `days` days or
+    /// without a due date, days are counted in the local time zone
+    pub fn agenda(&self, days: u32, filter: &AgendaFilter) -> Agenda {
+        let today = Local::now().date_naive();
+        self.task_index.agenda(today, &Local, days, filter)
+    }
+
+    pub fn get_reminders(&self) -> Vec<Reminder> {
+        self.task_index.get_reminders()
+    }
 }
 
 pub type HeldState = Mutex<NoterState>;
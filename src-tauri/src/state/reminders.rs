@@ -0,0 +1,94 @@
+use std::{path::PathBuf, sync::Mutex, thread, time::Duration};
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use tauri::{api::notification::Notification, AppHandle, Manager};
+
+use crate::{
+    state::HeldState,
+    types::{Reminder, ReminderSchedule},
+};
+
+pub const REMINDER_EVENT: &str = "reminder";
+
+/// Edits of the todo lists are picked up at least this often
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// Delivers task reminders on a background thread, as desktop notifications and as `reminder`
+/// events. Tauri v1 notifications can not report clicks, so the frontend lists the reminders
+/// and opens a task from the source and the task id once the user asks for it.
+///
+/// The schedule is only stored when reminders were delivered and once on exit, as the notes
+/// dir may be synced.
+pub struct ReminderScheduler {
+    notes_dir: PathBuf,
+    schedule: Mutex<ReminderSchedule>,
+}
+
+impl ReminderScheduler {
+    pub fn new(notes_dir: PathBuf) -> Self {
+        let schedule = ReminderSchedule::load(&notes_dir);
+        Self {
+            notes_dir,
+            schedule: Mutex::new(schedule),
+        }
+    }
+
+    pub fn store(&self) -> Result<()> {
+        match self.schedule.lock() {
+            Ok(schedule) => schedule.store(&self.notes_dir),
+            Err(_) => Err(anyhow!("Could not lock reminder schedule")),
+        }
+    }
+
+    /// The scheduler has to be managed by the app before
+    pub fn spawn(app: AppHandle) {
+        thread::spawn(move || loop {
+            let reminders = match app.state::<HeldState>().lock() {
+                Ok(state) => state.get_reminders(),
+                Err(_) => {
+                    eprintln!("Could not lock state");
+                    Vec::new()
+                }
+            };
+
+            let now = Utc::now();
+            let scheduler = app.state::<ReminderScheduler>();
+            let due = match scheduler.schedule.lock() {
+                Ok(mut schedule) => schedule.take_due(&reminders, now),
+                Err(_) => {
+                    eprintln!("Could not lock reminder schedule");
+                    Vec::new()
+                }
+            };
+
+            if !due.is_empty() {
+                for reminder in due {
+                    Self::notify(&app, reminder);
+                }
+                if let Err(e) = scheduler.store() {
+                    eprintln!("Could not store reminder schedule; {:?}", e);
+                }
+            }
+
+            let wait = ReminderSchedule::get_next(&reminders, now)
+                .and_then(|next| (next - now).to_std().ok())
+                .unwrap_or(MAX_WAIT);
+            thread::sleep(wait.min(MAX_WAIT));
+        });
+    }
+
+    fn notify(app: &AppHandle, reminder: Reminder) {
+        let result = Notification::new(&app.config().tauri.bundle.identifier)
+            .title(&reminder.content)
+            .body(reminder.source.get_name())
+            .show();
+        if let Err(e) = result {
+            eprintln!("Could not show notification; {:?}", e);
+        }
+
+        if let Err(e) = app.emit_all(REMINDER_EVENT, reminder) {
+            eprintln!("Could not emit {} event; {:?}", REMINDER_EVENT, e);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{channel, Sender},
+        Mutex,
+    },
+    thread,
+};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::types::{FileManager, FileStamp, Id, ThumbnailCache};
+
+pub const THUMBNAIL_CREATED_EVENT: &str = "thumbnail-created";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThumbnailCreated {
+    pub id: Id,
+}
+
+/// Generates thumbnails on a background thread so opening a folder full of images never blocks
+/// the app, every finished thumbnail is announced with a `thumbnail-created` event
+pub struct ThumbnailWorker {
+    file_manager: FileManager,
+    sender: Mutex<Sender<Id>>,
+}
+
+impl ThumbnailWorker {
+    pub fn new(app: AppHandle, file_manager: FileManager) -> Self {
+        let (sender, receiver) = channel::<Id>();
+        let worker_file_manager = file_manager.clone();
+
+        thread::spawn(move || {
+            let file_manager = worker_file_manager;
+            let cache = file_manager.get_thumbnail_cache();
+
+            for id in receiver {
+                let path = file_manager.get_path(&id);
+                let stamp = match Self::get_stamp(&path) {
+                    Ok(stamp) => stamp,
+                    Err(e) => {
+                        eprintln!("Could not read image; {:?}; {:?}", e, id);
+                        continue;
+                    }
+                };
+                // the same image may have been asked for again before its turn came
+                if cache.get(&id, &stamp).is_some() {
+                    continue;
+                }
+
+                match cache.generate(&id, &path, &stamp) {
+                    Ok(_) => {
+                        let event = ThumbnailCreated { id };
+                        if let Err(e) = app.emit_all(THUMBNAIL_CREATED_EVENT, event) {
+                            eprintln!("Could not emit {} event; {:?}", THUMBNAIL_CREATED_EVENT, e);
+                        }
+                    }
+                    Err(e) => eprintln!("Could not generate thumbnail; {:?}; {:?}", e, id),
+                }
+            }
+        });
+
+        Self {
+            file_manager,
+            sender: Mutex::new(sender),
+        }
+    }
+
+    fn get_stamp(path: &Path) -> Result<FileStamp> {
+        FileStamp::from_metadata(&fs::metadata(path)?)
+    }
+
+    /// Path of the thumbnail for the current version of the image, if it was generated already
+    pub fn get(&self, id: &Id) -> Option<PathBuf> {
+        let stamp = Self::get_stamp(&self.file_manager.get_path(id)).ok()?;
+        self.file_manager.get_thumbnail_cache().get(id, &stamp)
+    }
+
+    /// Returns whether the thumbnail is ready, a missing or outdated one is queued. Files that
+    /// have no thumbnail, like svg images or deleted files, are never ready.
+    pub fn request(&self, id: &Id) -> Result<bool> {
+        if !ThumbnailCache::supports(id) || !self.file_manager.get_path(id).is_file() {
+            return Ok(false);
+        }
+        if self.get(id).is_some() {
+            return Ok(true);
+        }
+
+        match self.sender.lock() {
+            Ok(sender) => sender.send(id.clone())?,
+            Err(_) => return Err(anyhow!("Could not lock thumbnail queue")),
+        }
+        Ok(false)
+    }
+}
@@ -0,0 +1,409 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use anyhow::Result;
+use notify_debouncer_full::{
+    new_debouncer,
+    notify::{
+        event::{ModifyKind, RenameMode},
+        EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+    },
+    DebounceEventResult, DebouncedEvent, Debouncer, FileIdMap,
+};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    state::{HeldState, NoterState},
+    types::{FilePreview, Id},
+};
+
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Batches with more changes than this (git checkout, sync of a whole folder) are reported as a
+/// single `Reloaded` event instead of one event per file
+const MAX_CHANGES_PER_BATCH: usize = 200;
+
+#[derive(Debug, Clone, Serialize)]
+pub enum VaultEvent {
+    Created {
+        id: Id,
+        file: FilePreview,
+    },
+    Modified {
+        id: Id,
+        file: FilePreview,
+    },
+    Deleted {
+        id: Id,
+    },
+    Renamed {
+        old_id: Id,
+        id: Id,
+        file: FilePreview,
+    },
+    Reloaded,
+}
+
+impl VaultEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Created { .. } => "file-created",
+            Self::Modified { .. } => "file-modified",
+            Self::Deleted { .. } => "file-deleted",
+            Self::Renamed { .. } => "file-renamed",
+            Self::Reloaded => "vault-reloaded",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Change {
+    Upsert(PathBuf),
+    Remove(PathBuf),
+    Rename(PathBuf, PathBuf),
+}
+
+/// Watches the notes folder for changes made outside of the app, keeps the index up to date
+/// and forwards the changes to the frontend
+pub struct VaultWatcher {
+    _debouncer: Mutex<Debouncer<RecommendedWatcher, FileIdMap>>,
+}
+
+impl VaultWatcher {
+    pub fn new(app: AppHandle, notes_dir: PathBuf) -> Result<Self> {
+        let dir = notes_dir.clone();
+        let mut debouncer = new_debouncer(
+            DEBOUNCE_TIMEOUT,
+            None,
+            move |result: DebounceEventResult| match result {
+                Ok(events) => handle_events(&app, &dir, events),
+                Err(errors) => errors
+                    .iter()
+                    .for_each(|e| eprintln!("Watcher error; {:?}", e)),
+            },
+        )?;
+
+        debouncer
+            .watcher()
+            .watch(&notes_dir, RecursiveMode::Recursive)?;
+        debouncer
+            .cache()
+            .add_root(&notes_dir, RecursiveMode::Recursive);
+
+        Ok(Self {
+            _debouncer: Mutex::new(debouncer),
+        })
+    }
+}
+
+fn is_ignored(path: &Path, notes_dir: &Path) -> bool {
+    match path.strip_prefix(notes_dir) {
+        Ok(relative) => relative
+            .components()
+            .any(|c| c.as_os_str().to_string_lossy().starts_with('.')),
+        Err(_) => true,
+    }
+}
+
+impl Change {
+    fn get_target(&self) -> &Path {
+        match self {
+            Self::Upsert(path) | Self::Remove(path) | Self::Rename(_, path) => path,
+        }
+    }
+}
+
+/// Source of the rename in the batch that ended at the path
+fn get_rename_source(changes: &[Change], target: &Path) -> Option<PathBuf> {
+    changes.iter().find_map(|change| match change {
+        Change::Rename(from, to) if to == target => Some(from.clone()),
+        _ => None,
+    })
+}
+
+/// Only the last change of every path matters, but a rename keeps its source, so that the
+/// source is unindexed even when its target changes again in the same batch
+fn push_change(changes: &mut Vec<Change>, change: Change) {
+    match change {
+        // the rename reads the target anyway, so its new content is picked up
+        Change::Upsert(path) if get_rename_source(changes, &path).is_some() => {}
+        Change::Upsert(path) => {
+            changes.retain(|c| c.get_target() != path);
+            changes.push(Change::Upsert(path));
+        }
+        Change::Remove(path) => {
+            let source = get_rename_source(changes, &path);
+            changes.retain(|c| c.get_target() != path);
+            if let Some(source) = source {
+                changes.push(Change::Remove(source));
+            }
+            changes.push(Change::Remove(path));
+        }
+        Change::Rename(from, to) => {
+            let source = get_rename_source(changes, &from).unwrap_or(from.clone());
+            changes.retain(|c| c.get_target() != from && c.get_target() != to);
+            match source == to {
+                true => changes.push(Change::Upsert(to)),
+                false => changes.push(Change::Rename(source, to)),
+            }
+        }
+    }
+}
+
+fn collect_changes(notes_dir: &Path, events: Vec<DebouncedEvent>) -> Vec<Change> {
+    let mut changes: Vec<Change> = Vec::new();
+
+    for event in events {
+        let paths = &event.paths;
+        let change = match event.kind {
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if paths.len() == 2 => {
+                Change::Rename(paths[0].clone(), paths[1].clone())
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) | EventKind::Remove(_) => {
+                match paths.first() {
+                    Some(path) => Change::Remove(path.clone()),
+                    None => continue,
+                }
+            }
+            EventKind::Create(_) | EventKind::Modify(_) => match paths.first() {
+                Some(path) => Change::Upsert(path.clone()),
+                None => continue,
+            },
+            _ => continue,
+        };
+
+        let change = match change {
+            Change::Rename(from, to) => {
+                match (is_ignored(&from, notes_dir), is_ignored(&to, notes_dir)) {
+                    (true, true) => continue,
+                    (true, false) => Change::Upsert(to),
+                    (false, true) => Change::Remove(from),
+                    (false, false) => Change::Rename(from, to),
+                }
+            }
+            Change::Upsert(ref path) | Change::Remove(ref path) if is_ignored(path, notes_dir) => {
+                continue
+            }
+            change => change,
+        };
+
+        push_change(&mut changes, change);
+    }
+
+    changes
+}
+
+fn index_path(state: &mut NoterState, path: &Path, events: &mut Vec<VaultEvent>) {
+    let notes_dir = state.get_notes_dir().clone();
+
+    if path.is_dir() {
+        match state.index_dir(path) {
+            Ok(files) => files.into_iter().for_each(|file| {
+                events.push(VaultEvent::Created {
+                    id: file.id.clone(),
+                    file,
+                })
+            }),
+            Err(e) => eprintln!("Could not read directory from fs; {:?}; {:?}", e, path),
+        }
+        return;
+    }
+
+    let id = match Id::new(path, &notes_dir) {
+        Ok(id) => id,
+        Err(_) => return,
+    };
+    if !path.is_file() || id.get_type().is_none() {
+        return;
+    }
+
+    let existed = state.is_indexed(&id);
+    match state.index_file(&id) {
+        Ok(file) if existed => events.push(VaultEvent::Modified { id, file }),
+        Ok(file) => events.push(VaultEvent::Created { id, file }),
+        Err(e) => eprintln!("Could not read file from fs; {:?}; {:?}", e, path),
+    }
+}
+
+fn unindex_path(state: &mut NoterState, path: &Path, events: &mut Vec<VaultEvent>) {
+    let id = match Id::new(path, state.get_notes_dir()) {
+        Ok(id) => id,
+        Err(_) => return,
+    };
+
+    state
+        .unindex_entry(&id)
+        .into_iter()
+        .for_each(|id| events.push(VaultEvent::Deleted { id }));
+}
+
+fn handle_events(app: &AppHandle, notes_dir: &Path, events: Vec<DebouncedEvent>) {
+    let changes = collect_changes(notes_dir, events);
+    if changes.is_empty() {
+        return;
+    }
+
+    let held_state = app.state::<HeldState>();
+    let mut state = match held_state.lock() {
+        Ok(state) => state,
+        Err(_) => {
+            eprintln!("Could not lock state");
+            return;
+        }
+    };
+
+    let mut vault_events = Vec::new();
+
+    if changes.len() > MAX_CHANGES_PER_BATCH {
+        match state.reindex() {
+            Ok(_) => vault_events.push(VaultEvent::Reloaded),
+            Err(e) => eprintln!("Could not reindex notes dir; {:?}", e),
+        }
+    } else {
+        for change in changes {
+            match change {
+                Change::Upsert(path) if path.exists() => {
+                    index_path(&mut state, &path, &mut vault_events)
+                }
+                Change::Upsert(path) | Change::Remove(path) => {
+                    unindex_path(&mut state, &path, &mut vault_events)
+                }
+                Change::Rename(from, to) => {
+                    let old_id = Id::new(&from, notes_dir);
+                    let id = Id::new(&to, notes_dir);
+
+                    match (old_id, id) {
+                        (Ok(old_id), Ok(id)) if to.is_file() && state.is_indexed(&old_id) => {
+                            state.unindex_entry(&old_id);
+                            match state.index_file(&id) {
+                                Ok(file) => {
+                                    vault_events.push(VaultEvent::Renamed { old_id, id, file })
+                                }
+                                Err(e) => {
+                                    eprintln!("Could not read file from fs; {:?}; {:?}", e, to);
+                                    vault_events.push(VaultEvent::Deleted { id: old_id });
+                                }
+                            }
+                        }
+                        _ => {
+                            unindex_path(&mut state, &from, &mut vault_events);
+                            index_path(&mut state, &to, &mut vault_events);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    drop(state);
+
+    for event in vault_events {
+        if let Err(e) = app.emit_all(event.name(), event.clone()) {
+            eprintln!("Could not emit {} event; {:?}", event.name(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify_debouncer_full::notify::{
+        event::{CreateKind, DataChange, RemoveKind},
+        Event,
+    };
+    use std::time::Instant;
+
+    fn event(kind: EventKind, paths: &[&str]) -> DebouncedEvent {
+        let event = paths
+            .iter()
+            .fold(Event::new(kind), |event, path| event.add_path(path.into()));
+        DebouncedEvent::new(event, Instant::now())
+    }
+
+    fn rename(from: &str, to: &str) -> DebouncedEvent {
+        let kind = EventKind::Modify(ModifyKind::Name(RenameMode::Both));
+        event(kind, &[from, to])
+    }
+
+    fn modify(path: &str) -> DebouncedEvent {
+        event(
+            EventKind::Modify(ModifyKind::Data(DataChange::Any)),
+            &[path],
+        )
+    }
+
+    #[test]
+    fn test_rename_and_modify() {
+        let notes_dir = Path::new("/notes");
+        let changes = collect_changes(
+            notes_dir,
+            vec![rename("/notes/a.md", "/notes/b.md"), modify("/notes/b.md")],
+        );
+        assert_eq!(
+            changes,
+            vec![Change::Rename("/notes/a.md".into(), "/notes/b.md".into())]
+        );
+
+        let changes = collect_changes(
+            notes_dir,
+            vec![
+                rename("/notes/a.md", "/notes/b.md"),
+                rename("/notes/b.md", "/notes/c.md"),
+            ],
+        );
+        assert_eq!(
+            changes,
+            vec![Change::Rename("/notes/a.md".into(), "/notes/c.md".into())]
+        );
+
+        let changes = collect_changes(
+            notes_dir,
+            vec![
+                rename("/notes/a.md", "/notes/b.md"),
+                event(EventKind::Remove(RemoveKind::File), &["/notes/b.md"]),
+            ],
+        );
+        assert_eq!(
+            changes,
+            vec![
+                Change::Remove("/notes/a.md".into()),
+                Change::Remove("/notes/b.md".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_create_and_remove() {
+        let changes = collect_changes(
+            Path::new("/notes"),
+            vec![
+                event(EventKind::Create(CreateKind::File), &["/notes/a.md"]),
+                modify("/notes/a.md"),
+                event(EventKind::Remove(RemoveKind::File), &["/notes/a.md"]),
+            ],
+        );
+        assert_eq!(changes, vec![Change::Remove("/notes/a.md".into())]);
+    }
+
+    #[test]
+    fn test_ignored_renames() {
+        let changes = collect_changes(
+            Path::new("/notes"),
+            vec![
+                rename("/notes/.trash/a.md", "/notes/a.md"),
+                rename("/notes/b.md", "/notes/.trash/b.md"),
+                rename("/notes/.noter/x.tmp", "/notes/.noter/x.json"),
+            ],
+        );
+        assert_eq!(
+            changes,
+            vec![
+                Change::Upsert("/notes/a.md".into()),
+                Change::Remove("/notes/b.md".into())
+            ]
+        );
+    }
+}
@@ -7,14 +7,23 @@ use tauri::PathResolver;
 const DEFAULT_WIDTH: f64 = 1920.0;
 const DEFAULT_HEIGHT: f64 = 1080.0;
 
+const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
 const APP_CONFIG_FILE: &str = "noter.json";
 
+fn default_trash_retention_days() -> Option<u32> {
+    Some(DEFAULT_TRASH_RETENTION_DAYS)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     notes_folders: Vec<PathBuf>,
     preffered_notes_folder: PathBuf,
     width: f64,
     height: f64,
+    /// Entries older than this are removed from the trash on startup, None keeps them forever
+    #[serde(default = "default_trash_retention_days")]
+    trash_retention_days: Option<u32>,
 }
 
 impl Config {
@@ -24,6 +33,7 @@ impl Config {
             preffered_notes_folder: notes_folder,
             width: DEFAULT_WIDTH,
             height: DEFAULT_HEIGHT,
+            trash_retention_days: default_trash_retention_days(),
         }
     }
 
@@ -52,4 +62,8 @@ impl Config {
     pub fn get_preffered_notes_folder(&self) -> PathBuf {
         self.preffered_notes_folder.clone()
     }
+
+    pub fn get_trash_retention_days(&self) -> Option<u32> {
+        self.trash_retention_days
+    }
 }
@@ -112,4 +112,26 @@ impl DataAdapter {
     pub fn delete_file(&mut self, id: &Id) {
         self.files.remove(id);
     }
+
+    /// Ids of the entry and everything nested under it
+    pub fn get_ids_under(&self, id: &Id) -> Vec<Id> {
+        self.files
+            .keys()
+            .filter(|key| key.is_under(id))
+            .cloned()
+            .collect()
+    }
+
+    /// Removes the entry with given id together with everything nested under it
+    pub fn delete_entries_under(&mut self, id: &Id) -> Vec<Id> {
+        let removed = self.get_ids_under(id);
+        removed.iter().for_each(|key| {
+            self.files.remove(key);
+        });
+        removed
+    }
+
+    pub fn clear(&mut self) {
+        self.files.clear();
+    }
 }
@@ -1,12 +1,14 @@
 use anyhow::{anyhow, Result};
 use std::{
-    collections::HashMap,
-    fs::{self, metadata, read_to_string},
+    fs::{self, metadata},
     path::{Path, PathBuf},
 };
 
-use super::{Directory, Entry, File, FileContent, FileType, Id};
+use super::{
+    Directory, Entry, File, FileContent, FilePreview, Frontmatter, Id, ThumbnailCache, Trash,
+};
 
+#[derive(Clone)]
 pub struct FileManager {
     notes_dir: PathBuf,
 }
@@ -40,32 +42,12 @@ impl FileManager {
             .ok_or_else(|| anyhow!("Notes dir is not a directory"))
     }
 
-    pub fn get_frontmatter(&self, id: &Id) -> Result<HashMap<String, String>> {
-        let path = self.get_path(id);
-
-        let content = read_to_string(&path)?;
-        let mut lines = content.lines();
-
-        let mut map = HashMap::new();
-
-        if lines.next() != Some("---") {
-            return Err(anyhow!("Invalid frontmatter"));
-        }
-
-        while let Some(line) = lines.next() {
-            if line == "---" {
-                break;
-            }
-
-            let parts: Vec<&str> = line.splitn(2, ": ").collect();
-            if parts.len() != 2 {
-                return Err(anyhow!("Invalid frontmatter"));
-            }
-
-            map.insert(parts[0].to_string(), parts[1].to_string());
+    /// Frontmatter of the note, `None` if the note has none
+    pub fn get_frontmatter(&self, id: &Id) -> Result<Option<Frontmatter>> {
+        match self.read(id)?.content {
+            FileContent::Note(note) => Ok(note.get_frontmatter().cloned()),
+            _ => Err(anyhow!("Not a note")),
         }
-
-        Ok(map)
     }
 
     pub fn read(&self, id: &Id) -> Result<File> {
@@ -93,13 +75,7 @@ impl FileManager {
             Err(_) => return Err(anyhow!("File could not be read")),
         };
 
-        let extension = path
-            .extension()
-            .ok_or_else(|| anyhow::anyhow!("File does not have extension"))
-            .map(|ext| ext.to_string_lossy().to_string())?;
-
-        let file_type =
-            FileType::from_str(&extension).ok_or_else(|| anyhow!("Invalid extension"))?;
+        let file_type = id.get_type().ok_or_else(|| anyhow!("Invalid extension"))?;
 
         let file_content = match FileContent::custom_deserialize(&body, file_type) {
             Ok(content) => content,
@@ -123,7 +99,7 @@ impl FileManager {
         };
 
         match fs::write(&path, content) {
-            Ok(_) => Ok(()),
+            Ok(_) => self.get_thumbnail_cache().invalidate(&file.id),
             Err(_) => Err(anyhow!("Could not write file")),
         }
     }
@@ -135,12 +111,43 @@ impl FileManager {
             return Err(anyhow!("File does not exist"));
         }
 
-        match fs::remove_file(&path) {
+        match self.get_trash().put(&path, id) {
             Ok(_) => Ok(()),
             Err(_) => Err(anyhow!("Could not delete file")),
         }
     }
 
+    pub fn get_trash(&self) -> Trash {
+        Trash::new(&self.notes_dir)
+    }
+
+    pub fn get_thumbnail_cache(&self) -> ThumbnailCache {
+        ThumbnailCache::new(&self.notes_dir)
+    }
+
+    /// Returns the id, or the id with " (n)" appended to the name if it is already taken
+    pub fn get_free_id(&self, id: &Id) -> Id {
+        let mut free_id = id.clone();
+        let mut n = 1;
+        while self.exists(&free_id) {
+            free_id = id.with_suffix(&format!(" ({})", n));
+            n += 1;
+        }
+        free_id
+    }
+
+    /// Moves an entry from the trash back to where it was deleted from
+    pub fn restore(&self, trash_id: &str) -> Result<Id> {
+        let trash = self.get_trash();
+        let entry = trash.find(trash_id)?;
+        let id = self.get_free_id(&entry.original_id);
+
+        match trash.take(trash_id, &self.get_path(&id)) {
+            Ok(_) => Ok(id),
+            Err(_) => Err(anyhow!("Could not restore file")),
+        }
+    }
+
     pub fn copy(&self, id: &Id, new_id: &Id) -> Result<()> {
         let path = self.get_path(id);
         let new_path = self.get_path(new_id);
@@ -155,35 +162,129 @@ impl FileManager {
         }
     }
 
-    pub fn rename(&self, id: &Id, new_name: &str) -> Result<Id> {
+    fn check_name(name: &str) -> Result<()> {
+        if name.trim().is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+            return Err(anyhow!("Invalid name"));
+        }
+        Ok(())
+    }
+
+    /// Id the entry would get after renaming it, files keep their extension
+    pub fn get_renamed_id(&self, id: &Id, new_name: &str) -> Result<Id> {
+        Self::check_name(new_name)?;
+
         let path = self.get_path(id);
         let notes_dir = self.get_notes_dir();
         let parent = id
             .get_parent(&notes_dir)
             .unwrap_or_else(|| Id::new(notes_dir, notes_dir).unwrap());
-        let new_id = Id::create_id(new_name, &parent, id.get_type().unwrap(), &notes_dir);
-        let new_path = self.get_path(&new_id);
 
-        if !self.exists(id) {
+        match path.is_dir() {
+            true => Ok(parent.join(new_name)),
+            false if id.get_type().is_none() => Err(anyhow!("Invalid extension")),
+            false => Ok(id.with_name(new_name)),
+        }
+    }
+
+    /// Id the entry would get after moving it into another directory
+    pub fn get_moved_id(&self, id: &Id, parent_id: &Id) -> Result<Id> {
+        if !self.get_path(parent_id).is_dir() {
+            return Err(anyhow!("Target is not a directory"));
+        }
+
+        if parent_id.is_under(id) {
+            return Err(anyhow!("Directory can not be moved into itself"));
+        }
+
+        Ok(parent_id.join(id.get_file_name()))
+    }
+
+    /// Moves file or directory to the place of `new_id`, which must not exist yet
+    pub fn relocate(&self, id: &Id, new_id: &Id) -> Result<()> {
+        let path = self.get_path(id);
+        let new_path = self.get_path(new_id);
+
+        if id.as_str().is_empty() || !self.exists(id) {
             return Err(anyhow!("File does not exist"));
         }
 
+        if new_id == id {
+            return Ok(());
+        }
+
+        if new_path.exists() {
+            return Err(anyhow!("File with this name already exists"));
+        }
+
+        if !new_path.parent().map(|p| p.is_dir()).unwrap_or(false) {
+            return Err(anyhow!("Target is not a directory"));
+        }
+
         match fs::rename(&path, &new_path) {
-            Ok(_) => Ok(new_id),
-            Err(_) => Err(anyhow!("Could not rename file")),
+            Ok(_) => Ok(()),
+            Err(_) => Err(anyhow!("Could not move file")),
         }
     }
 
-    pub fn read_dir<F: FnMut(&mut File)>(&self, dir: &Path, f: &mut F) -> Result<Directory> {
+    pub fn rename(&self, id: &Id, new_name: &str) -> Result<Id> {
+        let new_id = self.get_renamed_id(id, new_name)?;
+        self.relocate(id, &new_id)?;
+        Ok(new_id)
+    }
+
+    /// Moves file or directory into another directory, keeping its name
+    pub fn move_entry(&self, id: &Id, parent_id: &Id) -> Result<Id> {
+        let new_id = self.get_moved_id(id, parent_id)?;
+        self.relocate(id, &new_id)?;
+        Ok(new_id)
+    }
+
+    pub fn create_dir(&self, parent_id: &Id, name: &str) -> Result<Id> {
+        Self::check_name(name)?;
+
+        let id = parent_id.join(name);
+        let path = self.get_path(&id);
+
+        if !self.get_path(parent_id).is_dir() {
+            return Err(anyhow!("Parent is not a directory"));
+        }
+
+        if path.exists() {
+            return Err(anyhow!("Directory already exists"));
+        }
+
+        match fs::create_dir(&path) {
+            Ok(_) => Ok(id),
+            Err(_) => Err(anyhow!("Could not create directory")),
+        }
+    }
+
+    pub fn is_hidden(path: &Path) -> bool {
+        path.file_name()
+            .map(|name| name.to_string_lossy().starts_with('.'))
+            .unwrap_or(false)
+    }
+
+    /// Walks the directory tree without reading the files, `f` decides which preview is
+    /// shown for a file, files for which it returns None are left out
+    pub fn scan_dir<F: FnMut(Id, &fs::Metadata) -> Option<FilePreview>>(
+        &self,
+        dir: &Path,
+        f: &mut F,
+    ) -> Result<Directory> {
         let mut entries = Vec::new();
-        let metadata = fs::metadata(&self.notes_dir)?;
+        let metadata = fs::metadata(dir)?;
 
         for dir_entry in fs::read_dir(dir)? {
             let dir_entry = dir_entry?;
             let dir_entry_path = dir_entry.path();
 
+            if Self::is_hidden(&dir_entry_path) {
+                continue;
+            }
+
             if dir_entry_path.is_dir() {
-                let dir = match self.read_dir(&dir_entry_path, f) {
+                let dir = match self.scan_dir(&dir_entry_path, f) {
                     Ok(dir) => dir,
                     Err(e) => {
                         println!(
@@ -196,23 +297,17 @@ impl FileManager {
                 entries.push(Entry::Directory(dir));
             } else if dir_entry_path.is_file() {
                 let file_id = Id::new(&dir_entry_path, &self.notes_dir)?;
+                let file_metadata = dir_entry.metadata()?;
 
-                match self.read(&file_id) {
-                    Ok(mut file) => {
-                        f(&mut file);
-                        entries.push(Entry::File(file.get_preview()));
-                    }
-                    Err(e) => {
-                        println!("Could not read file from fs; {:?}; {:?}", e, dir_entry_path);
-                        continue;
-                    }
+                if let Some(preview) = f(file_id, &file_metadata) {
+                    entries.push(Entry::File(preview));
                 }
             }
         }
 
         let created_at = metadata.created()?;
         let modified_at = metadata.modified()?;
-        let id = Id::new(&dir, &self.notes_dir)?;
+        let id = Id::new(dir, &self.notes_dir)?;
 
         Ok(Directory::new(
             id,
@@ -223,6 +318,19 @@ impl FileManager {
         ))
     }
 
+    pub fn read_dir<F: FnMut(&mut File)>(&self, dir: &Path, f: &mut F) -> Result<Directory> {
+        self.scan_dir(dir, &mut |file_id, _| match self.read(&file_id) {
+            Ok(mut file) => {
+                f(&mut file);
+                Some(file.get_preview())
+            }
+            Err(e) => {
+                println!("Could not read file from fs; {:?}; {:?}", e, file_id);
+                None
+            }
+        })
+    }
+
     pub fn read_notes_dir(&self) -> Result<Directory> {
         self.read_dir(&self.notes_dir, &mut |_| {})
     }
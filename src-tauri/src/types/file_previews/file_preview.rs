@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::types::Id;
+use crate::types::{Id, IMAGE_EXTENSIONS};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq)]
 pub enum FileType {
@@ -27,11 +27,21 @@ impl FileType {
         match s {
             "md" => Some(Self::Note),
             "todo.csv" => Some(Self::TodoList),
-            "png" => Some(Self::Image),
             "csv" => Some(Self::Table),
+            _ if IMAGE_EXTENSIONS.contains(&s) => Some(Self::Image),
             _ => None,
         }
     }
+
+    /// Detects the type from a file name, `name.todo.csv` is a todo list and not a table
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        if name.ends_with(&format!(".{}", Self::TodoList.to_string())) {
+            return Some(Self::TodoList);
+        }
+
+        let (_, extension) = name.rsplit_once('.')?;
+        Self::from_str(&extension.to_lowercase())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
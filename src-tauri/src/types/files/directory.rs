@@ -21,6 +21,16 @@ impl Entry {
     }
 }
 
+/// Result of renaming or moving an entry, `moved` holds old and new ids of every file that
+/// changed its id, so open files can be followed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MovedEntry {
+    pub old_id: Id,
+    pub id: Id,
+    pub entry: Entry,
+    pub moved: Vec<(Id, Id)>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Directory {
     pub id: Id,
@@ -11,7 +11,7 @@ use crate::types::{FilePreview, FileType, Id};
 
 use super::{Image, Note, Table, TodoList};
 
-pub const IMAGE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+pub const IMAGE_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "gif", "webp", "svg"];
 
 pub trait FileSerializable {
     fn custom_serialize(&self) -> Result<Vec<u8>>;
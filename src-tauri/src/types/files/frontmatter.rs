@@ -0,0 +1,238 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+
+const DELIMITER: &str = "---";
+const END_DELIMITER: &str = "...";
+
+/// YAML block at the very top of a note, enclosed in `---` lines
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Frontmatter {
+    pub properties: Mapping,
+    /// The yaml as it was read, keys that did not change are written back from it, so their
+    /// order, formatting and comments survive saving
+    #[serde(default)]
+    pub raw: String,
+}
+
+/// Top-level key of the raw yaml together with the comments above it
+struct Block<'a> {
+    key: Option<Value>,
+    value: Value,
+    comments: &'a str,
+    text: &'a str,
+}
+
+impl Frontmatter {
+    pub fn new(properties: Mapping) -> Self {
+        Self {
+            properties,
+            raw: String::new(),
+        }
+    }
+
+    pub fn parse(raw: &str) -> Result<Self> {
+        let properties = match serde_yaml::from_str(raw)? {
+            Value::Mapping(properties) => properties,
+            Value::Null => Mapping::new(),
+            _ => return Err(anyhow!("Frontmatter is not a mapping")),
+        };
+
+        Ok(Self {
+            properties,
+            raw: raw.to_string(),
+        })
+    }
+
+    /// Splits note content into the frontmatter and the body. Content without a closing
+    /// delimiter or with invalid yaml is all body, so nothing gets lost on save.
+    pub fn split(content: &str) -> (Option<Self>, &str) {
+        let rest = match content
+            .strip_prefix("---\n")
+            .or_else(|| content.strip_prefix("---\r\n"))
+        {
+            Some(rest) => rest,
+            None => return (None, content),
+        };
+
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            let trimmed = line.trim_end_matches(['\n', '\r']);
+            if trimmed == DELIMITER || trimmed == END_DELIMITER {
+                return match Self::parse(&rest[..offset]) {
+                    Ok(frontmatter) => (Some(frontmatter), &rest[offset + line.len()..]),
+                    Err(_) => (None, content),
+                };
+            }
+            offset += line.len();
+        }
+
+        (None, content)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.properties.get(key)
+    }
+
+    fn blocks(raw: &str) -> Option<Vec<Block<'_>>> {
+        // (comments start, key start) of every top-level key
+        let mut starts = Vec::new();
+        let mut comments_start = None;
+        let mut offset = 0;
+
+        for line in raw.split_inclusive('\n') {
+            match line.chars().next() {
+                Some('\n' | '\r' | '#') => {
+                    comments_start.get_or_insert(offset);
+                }
+                Some(' ' | '\t' | '-') => comments_start = None,
+                _ => starts.push((comments_start.take().unwrap_or(offset), offset)),
+            }
+            offset += line.len();
+        }
+        let trailing = comments_start.unwrap_or(raw.len());
+
+        let mut blocks = Vec::new();
+        for (i, (comments, start)) in starts.iter().enumerate() {
+            let end = starts.get(i + 1).map(|(next, _)| *next).unwrap_or(trailing);
+            let text = &raw[*start..end];
+
+            let mapping: Mapping = serde_yaml::from_str(text).ok()?;
+            let (key, value) = match mapping.into_iter().collect::<Vec<_>>().as_slice() {
+                [(key, value)] => (key.clone(), value.clone()),
+                _ => return None,
+            };
+
+            blocks.push(Block {
+                key: Some(key),
+                value,
+                comments: &raw[*comments..*start],
+                text,
+            });
+        }
+
+        blocks.push(Block {
+            key: None,
+            value: Value::Null,
+            comments: "",
+            text: &raw[trailing..],
+        });
+        Some(blocks)
+    }
+
+    fn emit(properties: &Mapping) -> Result<String> {
+        match properties.is_empty() {
+            true => Ok(String::new()),
+            false => Ok(serde_yaml::to_string(properties)?),
+        }
+    }
+
+    /// Writes the properties back as yaml, only keys whose value changed are re-emitted, new
+    /// keys are appended at the end
+    pub fn to_yaml(&self) -> Result<String> {
+        if let Ok(original) = Self::parse(&self.raw) {
+            if original.properties == self.properties {
+                return Ok(self.raw.clone());
+            }
+        }
+
+        let blocks = match Self::blocks(&self.raw) {
+            Some(blocks) => blocks,
+            None => return Self::emit(&self.properties),
+        };
+
+        let mut yaml = String::new();
+        for block in &blocks {
+            let key = match &block.key {
+                Some(key) => key,
+                None => {
+                    yaml.push_str(block.text);
+                    continue;
+                }
+            };
+
+            if let Some(value) = self.properties.get(key) {
+                yaml.push_str(block.comments);
+                match *value == block.value {
+                    true => yaml.push_str(block.text),
+                    false => {
+                        let mut entry = Mapping::new();
+                        entry.insert(key.clone(), value.clone());
+                        yaml.push_str(&Self::emit(&entry)?);
+                    }
+                }
+            }
+        }
+
+        let added: Mapping = self
+            .properties
+            .iter()
+            .filter(|(key, _)| !blocks.iter().any(|block| block.key.as_ref() == Some(key)))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        yaml.push_str(&Self::emit(&added)?);
+
+        Ok(yaml)
+    }
+
+    /// Frontmatter block including the delimiters
+    pub fn serialize(&self) -> Result<String> {
+        let mut yaml = self.to_yaml()?;
+        if !yaml.is_empty() && !yaml.ends_with('\n') {
+            yaml.push('\n');
+        }
+        Ok(format!("{}\n{}{}\n", DELIMITER, yaml, DELIMITER))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTE: &str = "---\n# shown in the sidebar\ntitle: \"Weekly: review\"\ntags:\n- work\n- review\nmeta:\n  nested: true # keep me\n---\n# Body\n";
+
+    #[test]
+    fn test_split() {
+        let (frontmatter, body) = Frontmatter::split(NOTE);
+        let frontmatter = frontmatter.unwrap();
+        assert_eq!(body, "# Body\n");
+        assert_eq!(
+            frontmatter.get("title"),
+            Some(&Value::String("Weekly: review".to_string()))
+        );
+        assert_eq!(
+            frontmatter
+                .get("tags")
+                .unwrap()
+                .as_sequence()
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // a horizontal rule at the start of a note is not frontmatter
+        let (frontmatter, body) = Frontmatter::split("---\nsome text\n---\n");
+        assert!(frontmatter.is_none());
+        assert_eq!(body, "---\nsome text\n---\n");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let (frontmatter, _) = Frontmatter::split(NOTE);
+        let mut frontmatter = frontmatter.unwrap();
+        assert_eq!(
+            format!("{}# Body\n", frontmatter.serialize().unwrap()),
+            NOTE
+        );
+
+        frontmatter
+            .properties
+            .insert("title".into(), Value::String("Done".to_string()));
+        frontmatter.properties.insert("status".into(), 1.into());
+        frontmatter.properties.remove("tags");
+        assert_eq!(
+            frontmatter.to_yaml().unwrap(),
+            "# shown in the sidebar\ntitle: Done\nmeta:\n  nested: true # keep me\nstatus: 1\n"
+        );
+    }
+}
@@ -1,28 +1,193 @@
+use std::io::Cursor;
+
 use super::FileSerializable;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use chrono::NaiveDateTime;
+use exif::{In, Tag};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Image;
+const EXIF_DATE_FORMAT: &str = "%Y:%m:%d %H:%M:%S";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImageExif {
+    pub captured_at: Option<NaiveDateTime>,
+    /// EXIF orientation 1-8, 1 means the image is stored upright
+    pub orientation: Option<u32>,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+}
+
+/// Metadata of an image, the pixels are never sent to the frontend, it loads them by the id
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Image {
+    /// Format detected from the content, `None` if the image could not be recognised
+    format: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    size: u64,
+    exif: Option<ImageExif>,
+}
 
 impl Image {
     pub fn new(content: &[u8]) -> Self {
-        Self
+        let mut image = Self {
+            size: content.len() as u64,
+            ..Self::default()
+        };
+
+        if Self::is_svg(content) {
+            image.format = Some("svg".to_string());
+            (image.width, image.height) = Self::get_svg_dimensions(content);
+            return image;
+        }
+
+        let reader = match image::io::Reader::new(Cursor::new(content)).with_guessed_format() {
+            Ok(reader) => reader,
+            Err(_) => return image,
+        };
+        image.format = reader
+            .format()
+            .and_then(|format| format.extensions_str().first())
+            .map(|extension| extension.to_string());
+        if let Ok((width, height)) = reader.into_dimensions() {
+            image.width = Some(width);
+            image.height = Some(height);
+        }
+        image.exif = Self::read_exif(content);
+
+        image
     }
-}
 
-impl Default for Image {
-    fn default() -> Self {
-        Self
+    pub fn get_format(&self) -> Option<&String> {
+        self.format.as_ref()
+    }
+
+    pub fn get_dimensions(&self) -> Option<(u32, u32)> {
+        self.width.zip(self.height)
+    }
+
+    pub fn get_exif(&self) -> Option<&ImageExif> {
+        self.exif.as_ref()
+    }
+
+    fn is_svg(content: &[u8]) -> bool {
+        let start = String::from_utf8_lossy(&content[..content.len().min(1024)]).to_lowercase();
+        let start = start.trim_start();
+        start.starts_with("<svg") || (start.starts_with("<?xml") && start.contains("<svg"))
+    }
+
+    /// Reads `width` and `height` of the root element, falls back to the `viewBox`
+    fn get_svg_dimensions(content: &[u8]) -> (Option<u32>, Option<u32>) {
+        let text = String::from_utf8_lossy(content);
+        let root = match text.find("<svg") {
+            Some(start) => &text[start..start + text[start..].find('>').unwrap_or(0)],
+            None => return (None, None),
+        };
+
+        let attribute = |name: &str| {
+            let start = root.find(&format!(" {}=", name))? + name.len() + 2;
+            // unquoted values and other quote characters are not valid xml
+            let quote = root[start..]
+                .chars()
+                .next()
+                .filter(|c| matches!(c, '"' | '\''))?;
+            let value = root[start + quote.len_utf8()..].split(quote).next()?;
+            Some(value.to_string())
+        };
+        let length = |value: String| {
+            let number: String = value
+                .trim()
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '.')
+                .collect();
+            number.parse::<f64>().ok().map(|n| n.round() as u32)
+        };
+
+        let width = attribute("width").and_then(length);
+        let height = attribute("height").and_then(length);
+        if width.is_some() && height.is_some() {
+            return (width, height);
+        }
+
+        let view_box: Vec<f64> = attribute("viewBox")
+            .unwrap_or_default()
+            .split([' ', ','])
+            .filter_map(|n| n.parse().ok())
+            .collect();
+        match view_box.as_slice() {
+            [_, _, w, h] => (Some(w.round() as u32), Some(h.round() as u32)),
+            _ => (width, height),
+        }
+    }
+
+    fn read_exif(content: &[u8]) -> Option<ImageExif> {
+        let exif = exif::Reader::new()
+            .read_from_container(&mut Cursor::new(content))
+            .ok()?;
+
+        let text = |tag: Tag| {
+            exif.get_field(tag, In::PRIMARY)
+                .and_then(|field| match &field.value {
+                    exif::Value::Ascii(values) => values
+                        .first()
+                        .map(|value| String::from_utf8_lossy(value).trim().to_string()),
+                    _ => None,
+                })
+        };
+
+        Some(ImageExif {
+            captured_at: text(Tag::DateTimeOriginal)
+                .or_else(|| text(Tag::DateTime))
+                .and_then(|date| NaiveDateTime::parse_from_str(&date, EXIF_DATE_FORMAT).ok()),
+            orientation: exif
+                .get_field(Tag::Orientation, In::PRIMARY)
+                .and_then(|field| field.value.get_uint(0)),
+            camera_make: text(Tag::Make),
+            camera_model: text(Tag::Model),
+        })
     }
 }
 
 impl FileSerializable for Image {
     fn custom_deserialize(file_content: &[u8]) -> Result<Box<Self>> {
-        unimplemented!()
+        Ok(Box::new(Self::new(file_content)))
     }
 
     fn custom_serialize(&self) -> Result<Vec<u8>> {
-        unimplemented!()
+        Err(anyhow!("Images are read only"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_png() {
+        let mut content = Vec::new();
+        image::RgbImage::new(3, 2)
+            .write_to(
+                &mut Cursor::new(&mut content),
+                image::ImageOutputFormat::Png,
+            )
+            .unwrap();
+
+        let image = Image::new(&content);
+        assert_eq!(image.get_format().unwrap(), "png");
+        assert_eq!(image.get_dimensions(), Some((3, 2)));
+        assert!(image.get_exif().is_none());
+    }
+
+    #[test]
+    fn test_svg() {
+        let image = Image::new(b"<?xml version=\"1.0\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 24 16.4\"></svg>");
+        assert_eq!(image.get_format().unwrap(), "svg");
+        assert_eq!(image.get_dimensions(), Some((24, 16)));
+
+        let image = Image::new(b"<svg width='10px' height=\"20\"></svg>");
+        assert_eq!(image.get_dimensions(), Some((10, 20)));
+
+        let image = Image::new("<svg width=“10” height=20></svg>".as_bytes());
+        assert_eq!(image.get_dimensions(), None);
     }
 }
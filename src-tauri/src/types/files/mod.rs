@@ -1,13 +1,17 @@
 mod directory;
 mod file;
+mod frontmatter;
 mod image;
 mod note;
+mod recurrence;
 mod table;
 mod todo_list;
 
 pub use directory::*;
 pub use file::*;
+pub use frontmatter::*;
 pub use image::*;
 pub use note::*;
+pub use recurrence::*;
 pub use table::*;
 pub use todo_list::*;
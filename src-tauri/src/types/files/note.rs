@@ -2,86 +2,168 @@ use std::path::{Path, PathBuf};
 
 use crate::types::Id;
 
-use super::FileSerializable;
+use super::{FileSerializable, Frontmatter};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+/// Byte range of a link's text inside of the note body, line is 1-based
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkSpan {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Note {
+    #[serde(default)]
+    frontmatter: Option<Frontmatter>,
+    /// Content after the frontmatter, line numbers of links and search matches are relative to it
     body: String,
 }
 
 impl Note {
     pub fn new(body: String) -> Self {
-        Self { body }
+        Self {
+            frontmatter: None,
+            body,
+        }
     }
 
     pub fn get_body(&self) -> &String {
         &self.body
     }
 
-    pub fn find_links(&self, notes_dir: &Path) -> Vec<Id> {
-        let mut links = Vec::new();
+    pub fn set_body(&mut self, body: String) {
+        self.body = body;
+    }
+
+    pub fn get_frontmatter(&self) -> Option<&Frontmatter> {
+        self.frontmatter.as_ref()
+    }
 
-        self.body.lines().for_each(|line| {
-            line.match_indices("[[").for_each(|(start, _)| {
-                let mut end = start + 2;
-                let mut nested_count = 0;
-
-                while end < line.len() {
-                    match line.chars().nth(end) {
-                        Some('[') => {
-                            if line.chars().nth(end + 1) == Some('[') {
-                                nested_count += 1;
-                            } else {
-                                return;
+    pub fn set_frontmatter(&mut self, frontmatter: Option<Frontmatter>) {
+        self.frontmatter = frontmatter;
+    }
+
+    /// Finds positions of all `[[links]]` in the body, the spans cover only the text between
+    /// the brackets
+    pub fn find_link_spans(&self) -> Vec<LinkSpan> {
+        let mut spans = Vec::new();
+        let mut offset = 0;
+
+        self.body
+            .split_inclusive('\n')
+            .enumerate()
+            .for_each(|(line_index, raw_line)| {
+                let line_offset = offset;
+                offset += raw_line.len();
+                let line = raw_line.trim_end_matches('\n').trim_end_matches('\r');
+
+                // brackets are ascii, so it is safe to walk the bytes and slice at their positions
+                let bytes = line.as_bytes();
+                line.match_indices("[[").for_each(|(start, _)| {
+                    let mut end = start + 2;
+                    let mut nested_count = 0;
+
+                    while end < line.len() {
+                        match bytes.get(end).copied() {
+                            Some(b'[') => {
+                                if bytes.get(end + 1).copied() == Some(b'[') {
+                                    nested_count += 1;
+                                } else {
+                                    return;
+                                }
                             }
-                        }
-                        Some(']') => {
-                            if nested_count > 0 {
-                                nested_count -= 1;
-                            } else {
-                                if line.chars().nth(end + 1) == Some(']') {
-                                    break; // Found the closing brackets for valid link
+                            Some(b']') => {
+                                if nested_count > 0 {
+                                    nested_count -= 1;
+                                } else {
+                                    if bytes.get(end + 1).copied() == Some(b']') {
+                                        break; // Found the closing brackets for valid link
+                                    }
+                                    eprintln!("Unbalanced closing bracket: {}", line);
+                                    return;
                                 }
-                                eprintln!("Unbalanced closing bracket: {}", line);
-                                return;
                             }
-                        }
-                        _ => {}
-                    };
+                            _ => {}
+                        };
 
-                    end += 1;
-                }
+                        end += 1;
+                    }
 
-                if end >= line.len() {
-                    eprintln!("Unclosed link: {}", line);
-                    return;
-                }
-                let link = &line[start + 2..end];
-                let path = PathBuf::from(notes_dir).join(link);
-                let id = match Id::new(&path, notes_dir) {
-                    Ok(id) => links.push(id),
-                    Err(err) => {
-                        eprintln!("Error getting id for link {}: {}", link, err);
+                    if end >= line.len() {
+                        eprintln!("Unclosed link: {}", line);
+                        return;
                     }
-                };
+
+                    spans.push(LinkSpan {
+                        line: line_index + 1,
+                        start: line_offset + start + 2,
+                        end: line_offset + end,
+                    });
+                });
             });
+
+        spans
+    }
+
+    pub fn get_link_text(&self, span: &LinkSpan) -> &str {
+        &self.body[span.start..span.end]
+    }
+
+    pub fn find_links(&self, notes_dir: &Path) -> Vec<Id> {
+        let mut links = Vec::new();
+
+        self.find_link_spans().iter().for_each(|span| {
+            let link = self.get_link_text(span);
+            let path = PathBuf::from(notes_dir).join(link);
+            match Id::new(&path, notes_dir) {
+                Ok(id) => links.push(id),
+                Err(err) => {
+                    eprintln!("Error getting id for link {}: {}", link, err);
+                }
+            };
         });
 
         links
     }
+
+    /// Returns the body with text of the given links replaced, spans must not overlap
+    pub fn replace_links(&self, replacements: &[(LinkSpan, String)]) -> String {
+        let mut replacements: Vec<&(LinkSpan, String)> = replacements.iter().collect();
+        replacements.sort_by_key(|(span, _)| span.start);
+
+        let mut body = String::with_capacity(self.body.len());
+        let mut last = 0;
+        for (span, text) in replacements {
+            body.push_str(&self.body[last..span.start]);
+            body.push_str(text);
+            last = span.end;
+        }
+        body.push_str(&self.body[last..]);
+        body
+    }
 }
 
 impl FileSerializable for Note {
     fn custom_deserialize(file_content: &[u8]) -> Result<Box<Self>> {
+        let content = String::from_utf8_lossy(file_content);
+        let (frontmatter, body) = Frontmatter::split(&content);
+
         Ok(Box::new(Self {
-            body: String::from_utf8_lossy(file_content).to_string(),
+            frontmatter,
+            body: body.to_string(),
         }))
     }
 
     fn custom_serialize(&self) -> Result<Vec<u8>> {
-        Ok(self.body.as_bytes().to_vec())
+        let mut content = match &self.frontmatter {
+            Some(frontmatter) => frontmatter.serialize()?,
+            None => String::new(),
+        };
+        content.push_str(&self.body);
+        Ok(content.into_bytes())
     }
 }
 
@@ -124,6 +206,22 @@ mod tests {
         assert_eq!(links[0].to_string(), "link2");
     }
 
+    #[test]
+    fn test_replace_links() {
+        let note = Note::new("# Ünïcode\r\n[[a]] and [[b|alias]]\n[[c]]".to_string());
+        let spans = note.find_link_spans();
+        assert_eq!(spans.len(), 3);
+        assert_eq!(note.get_link_text(&spans[1]), "b|alias");
+        assert_eq!(spans[1].line, 2);
+        assert_eq!(spans[2].line, 3);
+
+        let body = note.replace_links(&[
+            (spans[2], "folder/c".to_string()),
+            (spans[0], "x".to_string()),
+        ]);
+        assert_eq!(body, "# Ünïcode\r\n[[x]] and [[b|alias]]\n[[folder/c]]");
+    }
+
     #[test]
     fn test_non_ascii_chars() {
         let note = Note::new("[[Certifikační autorita]]".to_string());
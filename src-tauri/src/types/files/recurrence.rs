@@ -0,0 +1,193 @@
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Repeat {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Repeat {
+    pub fn from_name(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "daily" => Some(Self::Daily),
+            "weekly" => Some(Self::Weekly),
+            "monthly" => Some(Self::Monthly),
+            "yearly" => Some(Self::Yearly),
+            _ => None,
+        }
+    }
+
+    /// Date of the occurrence after `date`, every `interval` days, weeks, months or years.
+    ///
+    /// `weekdays` move daily repeats on to the next of those days and make weekly repeats fall
+    /// on each of them, monthly and yearly repeats ignore them. Repeats from the last day of a
+    /// month stay on the last day, other days are clamped to the end of shorter months.
+    pub fn next(&self, date: DateTime<Utc>, interval: u32, weekdays: &[Weekday]) -> DateTime<Utc> {
+        let interval = interval.max(1);
+        let time = date.time();
+        let day = date.date_naive();
+
+        let next = match self {
+            Self::Daily => {
+                let mut next = day + Days::new(interval.into());
+                // one of the days is reached within a week
+                for _ in 0..7 {
+                    if weekdays.is_empty() || weekdays.contains(&next.weekday()) {
+                        break;
+                    }
+                    next = next + Days::new(1);
+                }
+                next
+            }
+            Self::Weekly if !weekdays.is_empty() => Self::next_weekday(day, interval, weekdays),
+            Self::Weekly => day + Days::new(7 * u64::from(interval)),
+            Self::Monthly => Self::add_months(day, interval),
+            Self::Yearly => Self::add_months(day, interval * 12),
+        };
+
+        next.and_time(time).and_utc()
+    }
+
+    /// The next of the weekdays later in the same week, or the first of them `interval` weeks
+    /// later, weeks start on monday
+    fn next_weekday(day: NaiveDate, interval: u32, weekdays: &[Weekday]) -> NaiveDate {
+        let current = day.weekday().num_days_from_monday();
+        let later = weekdays
+            .iter()
+            .map(|weekday| weekday.num_days_from_monday())
+            .filter(|weekday| *weekday > current)
+            .min();
+
+        match later {
+            Some(weekday) => day + Days::new((weekday - current).into()),
+            None => {
+                let first = weekdays
+                    .iter()
+                    .map(|weekday| weekday.num_days_from_monday())
+                    .min()
+                    .unwrap_or(current);
+                let week_start = day - Days::new(current.into());
+                week_start + Days::new(7 * u64::from(interval) + u64::from(first))
+            }
+        }
+    }
+
+    fn is_last_day_of_month(day: NaiveDate) -> bool {
+        day.succ_opt()
+            .is_none_or(|next| next.month() != day.month())
+    }
+
+    fn add_months(day: NaiveDate, months: u32) -> NaiveDate {
+        // chrono clamps the day to the end of shorter months
+        let next = day + Months::new(months);
+        if !Self::is_last_day_of_month(day) {
+            return next;
+        }
+
+        let mut last = next;
+        while let Some(following) = last.succ_opt().filter(|d| d.month() == next.month()) {
+            last = following;
+        }
+        last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    fn date(date: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(&format!("{} 09:30", date), "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc()
+    }
+
+    #[test]
+    fn test_month_end() {
+        let next = Repeat::Monthly.next(date("2024-01-31"), 1, &[]);
+        assert_eq!(next, date("2024-02-29"));
+        // the last day of february stays the last day of the month
+        assert_eq!(Repeat::Monthly.next(next, 1, &[]), date("2024-03-31"));
+        assert_eq!(
+            Repeat::Monthly.next(date("2023-01-30"), 1, &[]),
+            date("2023-02-28")
+        );
+        assert_eq!(
+            Repeat::Monthly.next(date("2024-01-15"), 3, &[]),
+            date("2024-04-15")
+        );
+        assert_eq!(
+            Repeat::Monthly.next(date("2024-11-30"), 2, &[]),
+            date("2025-01-31")
+        );
+    }
+
+    #[test]
+    fn test_leap_year() {
+        let next = Repeat::Yearly.next(date("2024-02-29"), 1, &[]);
+        assert_eq!(next, date("2025-02-28"));
+        assert_eq!(
+            Repeat::Yearly.next(date("2027-02-28"), 1, &[]),
+            date("2028-02-29")
+        );
+        assert_eq!(
+            Repeat::Yearly.next(date("2024-02-29"), 4, &[]),
+            date("2028-02-29")
+        );
+        assert_eq!(
+            Repeat::Daily.next(date("2024-02-28"), 1, &[]),
+            date("2024-02-29")
+        );
+        assert_eq!(
+            Repeat::Daily.next(date("2023-02-28"), 1, &[]),
+            date("2023-03-01")
+        );
+    }
+
+    #[test]
+    fn test_weekdays() {
+        // 2024-05-01 is a wednesday
+        let days = [Weekday::Mon, Weekday::Fri];
+        assert_eq!(
+            Repeat::Weekly.next(date("2024-05-01"), 1, &days),
+            date("2024-05-03")
+        );
+        assert_eq!(
+            Repeat::Weekly.next(date("2024-05-03"), 1, &days),
+            date("2024-05-06")
+        );
+        assert_eq!(
+            Repeat::Weekly.next(date("2024-05-03"), 2, &days),
+            date("2024-05-13")
+        );
+        assert_eq!(
+            Repeat::Weekly.next(date("2024-05-01"), 2, &[]),
+            date("2024-05-15")
+        );
+
+        let work_days = [
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+        ];
+        assert_eq!(
+            Repeat::Daily.next(date("2024-05-03"), 1, &work_days),
+            date("2024-05-06")
+        );
+        // the interval would only ever land on wednesdays
+        assert_eq!(
+            Repeat::Daily.next(date("2024-05-01"), 7, &[Weekday::Mon]),
+            date("2024-05-13")
+        );
+        assert_eq!(
+            Repeat::Daily.next(date("2024-05-03"), 2, &work_days),
+            date("2024-05-06")
+        );
+    }
+}
@@ -1,22 +1,267 @@
 use super::FileSerializable;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Table;
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%d.%m.%Y", "%m/%d/%Y"];
 
-impl Default for Table {
-    fn default() -> Self {
-        Self
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColumnType {
+    Text,
+    Number,
+    Date,
+    Boolean,
+}
+
+impl ColumnType {
+    fn matches(&self, value: &str) -> bool {
+        match self {
+            Self::Text => true,
+            Self::Number => value.parse::<f64>().is_ok(),
+            Self::Date => {
+                DateTime::parse_from_rfc3339(value).is_ok()
+                    || DATE_FORMATS
+                        .iter()
+                        .any(|format| NaiveDate::parse_from_str(value, format).is_ok())
+            }
+            Self::Boolean => {
+                value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false")
+            }
+        }
+    }
+
+    /// The most specific type all non-empty values fit, columns without values are text
+    pub fn infer<'a, I: Iterator<Item = &'a str>>(values: I) -> Self {
+        let values: Vec<&str> = values.map(str::trim).filter(|v| !v.is_empty()).collect();
+        if values.is_empty() {
+            return Self::Text;
+        }
+
+        [Self::Boolean, Self::Number, Self::Date]
+            .into_iter()
+            .find(|column_type| values.iter().all(|value| column_type.matches(value)))
+            .unwrap_or(Self::Text)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Column {
+    pub name: String,
+    pub column_type: ColumnType,
+}
+
+/// CSV file with a header row, cells are kept as written so the file round-trips unchanged and
+/// column types are only inferred for displaying and sorting
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Rows longer than the header get unnamed columns, so no cell is lost
+    pub fn new(mut headers: Vec<String>, rows: Vec<Vec<String>>) -> Self {
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        if width > headers.len() {
+            headers.resize(width, String::new());
+        }
+
+        let mut table = Self {
+            columns: headers
+                .into_iter()
+                .map(|name| Column {
+                    name,
+                    column_type: ColumnType::Text,
+                })
+                .collect(),
+            rows,
+        };
+        table.normalize();
+        table
+    }
+
+    pub fn get_columns(&self) -> &Vec<Column> {
+        &self.columns
+    }
+
+    pub fn get_rows(&self) -> &Vec<Vec<String>> {
+        &self.rows
+    }
+
+    /// Pads rows to the number of columns and infers the column types again
+    fn normalize(&mut self) {
+        let width = self.columns.len();
+        self.rows
+            .iter_mut()
+            .for_each(|row| row.resize(width, String::new()));
+
+        for (i, column) in self.columns.iter_mut().enumerate() {
+            column.column_type = ColumnType::infer(self.rows.iter().map(|row| row[i].as_str()));
+        }
+    }
+
+    fn check_row(&self, row: usize) -> Result<()> {
+        match row < self.rows.len() {
+            true => Ok(()),
+            false => Err(anyhow!("Row {} does not exist", row)),
+        }
+    }
+
+    fn check_column(&self, column: usize) -> Result<()> {
+        match column < self.columns.len() {
+            true => Ok(()),
+            false => Err(anyhow!("Column {} does not exist", column)),
+        }
+    }
+
+    pub fn set_cell(&mut self, row: usize, column: usize, value: String) -> Result<()> {
+        self.check_row(row)?;
+        self.check_column(column)?;
+        self.rows[row][column] = value;
+        self.normalize();
+        Ok(())
+    }
+
+    /// Inserts an empty row at `index`, or at the end if there is no index
+    pub fn insert_row(&mut self, index: Option<usize>) -> Result<()> {
+        let index = index.unwrap_or(self.rows.len());
+        if index > self.rows.len() {
+            return Err(anyhow!("Row {} does not exist", index));
+        }
+        self.rows
+            .insert(index, vec![String::new(); self.columns.len()]);
+        Ok(())
+    }
+
+    pub fn delete_row(&mut self, index: usize) -> Result<()> {
+        self.check_row(index)?;
+        self.rows.remove(index);
+        self.normalize();
+        Ok(())
+    }
+
+    /// Inserts an empty column at `index`, or at the end if there is no index
+    pub fn insert_column(&mut self, index: Option<usize>, name: String) -> Result<()> {
+        let index = index.unwrap_or(self.columns.len());
+        if index > self.columns.len() {
+            return Err(anyhow!("Column {} does not exist", index));
+        }
+        self.columns.insert(
+            index,
+            Column {
+                name,
+                column_type: ColumnType::Text,
+            },
+        );
+        self.rows
+            .iter_mut()
+            .for_each(|row| row.insert(index, String::new()));
+        Ok(())
+    }
+
+    pub fn rename_column(&mut self, index: usize, name: String) -> Result<()> {
+        self.check_column(index)?;
+        self.columns[index].name = name;
+        Ok(())
+    }
+
+    pub fn delete_column(&mut self, index: usize) -> Result<()> {
+        self.check_column(index)?;
+        self.columns.remove(index);
+        self.rows.iter_mut().for_each(|row| {
+            row.remove(index);
+        });
+        Ok(())
+    }
+
+    pub fn move_column(&mut self, from: usize, to: usize) -> Result<()> {
+        self.check_column(from)?;
+        self.check_column(to)?;
+
+        let column = self.columns.remove(from);
+        self.columns.insert(to, column);
+        self.rows.iter_mut().for_each(|row| {
+            let cell = row.remove(from);
+            row.insert(to, cell);
+        });
+        Ok(())
     }
 }
 
 impl FileSerializable for Table {
     fn custom_deserialize(file_content: &[u8]) -> Result<Box<Self>> {
-        unimplemented!()
+        let mut rdr = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(file_content);
+
+        let headers = rdr.headers()?.iter().map(|h| h.to_string()).collect();
+        let mut rows = Vec::new();
+        for record in rdr.records() {
+            rows.push(record?.iter().map(|cell| cell.to_string()).collect());
+        }
+
+        Ok(Box::new(Self::new(headers, rows)))
     }
 
     fn custom_serialize(&self) -> Result<Vec<u8>> {
-        unimplemented!()
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+
+        if !self.columns.is_empty() {
+            wtr.write_record(self.columns.iter().map(|column| &column.name))?;
+            for row in &self.rows {
+                wtr.write_record(row)?;
+            }
+        }
+
+        wtr.flush()?;
+
+        Ok(wtr.into_inner()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str =
+        "name,price,bought,in stock\n\"Milk, 1l\",1.5,2024-01-02,true\nBread,2,,false\n";
+
+    #[test]
+    fn test_round_trip() {
+        let table = Table::custom_deserialize(CSV.as_bytes()).unwrap();
+        let types: Vec<ColumnType> = table.get_columns().iter().map(|c| c.column_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                ColumnType::Text,
+                ColumnType::Number,
+                ColumnType::Date,
+                ColumnType::Boolean
+            ]
+        );
+        assert_eq!(table.get_rows()[0][0], "Milk, 1l");
+        assert_eq!(
+            String::from_utf8(table.custom_serialize().unwrap()).unwrap(),
+            CSV
+        );
+    }
+
+    #[test]
+    fn test_edit() {
+        let mut table = Table::custom_deserialize(CSV.as_bytes()).unwrap();
+        table.set_cell(1, 1, "cheap".to_string()).unwrap();
+        assert_eq!(table.get_columns()[1].column_type, ColumnType::Text);
+
+        table.insert_column(Some(0), "id".to_string()).unwrap();
+        table.move_column(0, 4).unwrap();
+        table.delete_column(3).unwrap();
+        table.insert_row(None).unwrap();
+        table.delete_row(0).unwrap();
+        assert!(table.set_cell(5, 0, String::new()).is_err());
+
+        assert_eq!(
+            String::from_utf8(table.custom_serialize().unwrap()).unwrap(),
+            "name,price,bought,id\nBread,cheap,,\n,,,\n"
+        );
     }
 }
@@ -1,72 +1,1021 @@
 use crate::types::Id;
-use anyhow::Result;
-use chrono::{DateTime, Utc};
+use anyhow::{anyhow, Result};
+use chrono::{
+    DateTime, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, TimeZone, Utc, Weekday,
+};
 use csv;
-use serde::{Deserialize, Serialize};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
 
-use super::FileSerializable;
+use super::{FileSerializable, Repeat};
 
-#[derive(Debug, Serialize, Deserialize)]
-pub enum Repeat {
-    Daily,
-    Weekly,
-    Monthly,
-    Yearly,
-}
+/// Version of the csv schema, written as the first line of every list. Lists without it were
+/// written before the schema was versioned and are read as version 1. Version 3 added the
+/// repeat interval and weekdays.
+pub const TODO_LIST_VERSION: u32 = 3;
+
+const VERSION_PREFIX: &str = "# noter todo list v";
 
-#[derive(Debug, Serialize, Deserialize)]
+const COLUMNS: [&str; 18] = [
+    "task_id",
+    "parent_id",
+    "task_type",
+    "content",
+    "description",
+    "project",
+    "attachment",
+    "priority",
+    "is_completed",
+    "created_at",
+    "completed_at",
+    "start_date",
+    "due_date",
+    "reminder",
+    "repeat",
+    "repeat_interval",
+    "repeat_weekdays",
+    "tags",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum TaskType {
+    #[default]
     Task,
     CheckList,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+fn default_repeat_interval() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
+    /// Assigned by the list when a task is added, so new tasks may leave it out
+    #[serde(default)]
     task_id: u32,
+    #[serde(default)]
     parent_id: Option<u32>,
+    #[serde(default)]
     task_type: TaskType,
     content: String,
+    #[serde(default)]
     description: String,
+    #[serde(default)]
     project: String,
-    attachment: Id,
+    #[serde(default)]
+    attachment: Option<Id>,
+    /// Higher is more important, 0 means no priority
+    #[serde(default)]
     priority: u8,
+    #[serde(default)]
     is_completed: bool,
+    #[serde(default = "Utc::now")]
     created_at: DateTime<Utc>,
+    #[serde(default)]
     completed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
     start_date: Option<DateTime<Utc>>,
-    due_date: DateTime<Utc>,
+    #[serde(default)]
+    due_date: Option<DateTime<Utc>>,
+    #[serde(default)]
     reminder: Option<DateTime<Utc>>,
+    #[serde(default)]
     repeat: Option<Repeat>,
+    /// Repeats every n days, weeks, months or years
+    #[serde(default = "default_repeat_interval")]
+    repeat_interval: u32,
+    #[serde(default)]
+    repeat_weekdays: Vec<Weekday>,
+    /// Space or comma separated tags
+    #[serde(default)]
+    tags: String,
+    /// Values of columns this version does not know, kept so they are written back
+    #[serde(default)]
+    extra: HashMap<String, String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+impl Task {
+    pub fn new(task_id: u32, content: String) -> Self {
+        Self {
+            task_id,
+            parent_id: None,
+            task_type: TaskType::Task,
+            content,
+            description: String::new(),
+            project: String::new(),
+            attachment: None,
+            priority: 0,
+            is_completed: false,
+            created_at: Utc::now(),
+            completed_at: None,
+            start_date: None,
+            due_date: None,
+            reminder: None,
+            repeat: None,
+            repeat_interval: default_repeat_interval(),
+            repeat_weekdays: Vec::new(),
+            tags: String::new(),
+            extra: HashMap::new(),
+        }
+    }
+
+    pub fn get_task_id(&self) -> u32 {
+        self.task_id
+    }
+
+    pub fn get_parent_id(&self) -> Option<u32> {
+        self.parent_id
+    }
+
+    /// Lists repair parents that are missing or make a cycle when they are created
+    pub fn set_parent_id(&mut self, parent_id: Option<u32>) {
+        self.parent_id = parent_id;
+    }
+
+    pub fn get_task_type(&self) -> TaskType {
+        self.task_type
+    }
+
+    pub fn set_task_type(&mut self, task_type: TaskType) {
+        self.task_type = task_type;
+    }
+
+    pub fn get_content(&self) -> &String {
+        &self.content
+    }
+
+    pub fn set_content(&mut self, content: String) {
+        self.content = content;
+    }
+
+    pub fn get_project(&self) -> &String {
+        &self.project
+    }
+
+    pub fn set_project(&mut self, project: String) {
+        self.project = project;
+    }
+
+    pub fn get_priority(&self) -> u8 {
+        self.priority
+    }
+
+    pub fn set_priority(&mut self, priority: u8) {
+        self.priority = priority;
+    }
+
+    pub fn get_created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn set_created_at(&mut self, created_at: DateTime<Utc>) {
+        self.created_at = created_at;
+    }
+
+    pub fn get_start_date(&self) -> Option<DateTime<Utc>> {
+        self.start_date
+    }
+
+    pub fn set_start_date(&mut self, start_date: Option<DateTime<Utc>>) {
+        self.start_date = start_date;
+    }
+
+    pub fn get_due_date(&self) -> Option<DateTime<Utc>> {
+        self.due_date
+    }
+
+    pub fn set_due_date(&mut self, due_date: Option<DateTime<Utc>>) {
+        self.due_date = due_date;
+    }
+
+    /// Day the task is due in `tz`. Due dates without a time are stored as UTC midnight and
+    /// keep their day in every time zone.
+    pub fn get_due_day<Tz: TimeZone>(&self, tz: &Tz) -> Option<NaiveDate> {
+        let due = self.due_date?;
+        match due.time() == NaiveTime::MIN {
+            true => Some(due.date_naive()),
+            false => Some(due.with_timezone(tz).date_naive()),
+        }
+    }
+
+    pub fn get_reminder(&self) -> Option<DateTime<Utc>> {
+        self.reminder
+    }
+
+    pub fn set_reminder(&mut self, reminder: Option<DateTime<Utc>>) {
+        self.reminder = reminder;
+    }
+
+    pub fn get_repeat(&self) -> Option<Repeat> {
+        self.repeat
+    }
+
+    pub fn get_repeat_interval(&self) -> u32 {
+        self.repeat_interval
+    }
+
+    pub fn get_repeat_weekdays(&self) -> &Vec<Weekday> {
+        &self.repeat_weekdays
+    }
+
+    /// The interval is at least 1, weekdays only matter for daily and weekly repeats
+    pub fn set_repeat(&mut self, repeat: Option<Repeat>, interval: u32, weekdays: Vec<Weekday>) {
+        self.repeat = repeat;
+        self.repeat_interval = interval.max(1);
+        self.repeat_weekdays = weekdays;
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.is_completed
+    }
+
+    /// Marks the task as done or not done, the completion time is kept up to date with it
+    pub fn set_completed(&mut self, completed: bool) {
+        if self.is_completed == completed {
+            return;
+        }
+        self.is_completed = completed;
+        self.completed_at = completed.then(Utc::now);
+    }
+
+    pub fn get_completed_at(&self) -> Option<DateTime<Utc>> {
+        self.completed_at
+    }
+
+    /// Sets whether the task is done and when, as read from another format. Open tasks have no
+    /// completion time, while done tasks may miss it.
+    pub fn set_completion(&mut self, completed: bool, completed_at: Option<DateTime<Utc>>) {
+        self.is_completed = completed;
+        self.completed_at = completed_at.filter(|_| completed);
+    }
+
+    pub fn get_description(&self) -> &String {
+        &self.description
+    }
+
+    pub fn set_description(&mut self, description: String) {
+        self.description = description;
+    }
+
+    /// The occurrence following this one, with its dates moved to the next date of the repeat.
+    /// Start date and reminder keep their distance to the due date.
+    pub fn get_next_occurrence(&self, task_id: u32) -> Option<Self> {
+        let repeat = self.repeat?;
+        let anchor = self.due_date.or(self.start_date).or(self.reminder);
+        let shift = |date: Option<DateTime<Utc>>| {
+            let anchor = anchor?;
+            let next = repeat.next(anchor, self.repeat_interval, &self.repeat_weekdays);
+            date.map(|date| next + (date - anchor))
+        };
+
+        Some(Self {
+            task_id,
+            is_completed: false,
+            created_at: Utc::now(),
+            completed_at: None,
+            start_date: shift(self.start_date),
+            due_date: shift(self.due_date),
+            reminder: shift(self.reminder),
+            ..self.clone()
+        })
+    }
+
+    pub fn get_tags(&self) -> Vec<String> {
+        self.tags
+            .split([',', ' '])
+            .filter(|tag| !tag.is_empty())
+            .map(|tag| tag.trim_start_matches('#').to_string())
+            .collect()
+    }
+
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        self.tags = tags.join(" ");
+    }
+
+    fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+        if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+            return Some(date.into());
+        }
+        if let Ok(date) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
+            return Some(date.and_utc());
+        }
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|date| date.and_utc())
+    }
+
+    fn format_date(date: &Option<DateTime<Utc>>) -> String {
+        date.map(|date| date.to_rfc3339_opts(SecondsFormat::Secs, true))
+            .unwrap_or_default()
+    }
+
+    /// Builds a task from a csv row by column name. Missing or unreadable values fall back to
+    /// their defaults, only the task id is required and a missing one is assigned later.
+    fn from_row(mut row: HashMap<String, String>) -> (Option<u32>, Self) {
+        let mut take = |column: &str| row.remove(column).unwrap_or_default().trim().to_string();
+
+        let task_id = take("task_id").parse().ok();
+        let mut task = Self::new(task_id.unwrap_or(0), take("content"));
+        task.parent_id = take("parent_id").parse().ok();
+        task.task_type = match take("task_type").to_lowercase().as_str() {
+            "checklist" => TaskType::CheckList,
+            _ => TaskType::Task,
+        };
+        task.description = take("description");
+        task.project = take("project");
+        task.attachment = Some(take("attachment"))
+            .filter(|attachment| !attachment.is_empty())
+            .map(Id::from_string);
+        task.priority = take("priority").parse().unwrap_or(0);
+        task.is_completed = matches!(
+            take("is_completed").to_lowercase().as_str(),
+            "true" | "1" | "x" | "yes"
+        );
+        if let Some(created_at) = Self::parse_date(&take("created_at")) {
+            task.created_at = created_at;
+        }
+        task.completed_at = Self::parse_date(&take("completed_at"));
+        task.start_date = Self::parse_date(&take("start_date"));
+        task.due_date = Self::parse_date(&take("due_date"));
+        task.reminder = Self::parse_date(&take("reminder"));
+        task.repeat = Repeat::from_name(&take("repeat"));
+        task.repeat_interval = take("repeat_interval").parse().unwrap_or(1).max(1);
+        task.repeat_weekdays = take("repeat_weekdays")
+            .split([',', ' '])
+            .filter_map(|weekday| weekday.parse().ok())
+            .collect();
+        task.tags = take("tags");
+        task.extra = row;
+
+        (task_id, task)
+    }
+
+    fn to_row(&self, extra_columns: &[String]) -> Vec<String> {
+        let mut row = vec![
+            self.task_id.to_string(),
+            self.parent_id.map(|id| id.to_string()).unwrap_or_default(),
+            format!("{:?}", self.task_type),
+            self.content.clone(),
+            self.description.clone(),
+            self.project.clone(),
+            self.attachment
+                .as_ref()
+                .map(|id| id.as_str().to_string())
+                .unwrap_or_default(),
+            self.priority.to_string(),
+            self.is_completed.to_string(),
+            Self::format_date(&Some(self.created_at)),
+            Self::format_date(&self.completed_at),
+            Self::format_date(&self.start_date),
+            Self::format_date(&self.due_date),
+            Self::format_date(&self.reminder),
+            self.repeat
+                .map(|repeat| format!("{:?}", repeat))
+                .unwrap_or_default(),
+            self.repeat_interval.to_string(),
+            self.repeat_weekdays
+                .iter()
+                .map(|weekday| weekday.to_string())
+                .collect::<Vec<String>>()
+                .join(" "),
+            self.tags.clone(),
+        ];
+        row.extend(
+            extra_columns
+                .iter()
+                .map(|column| self.extra.get(column).cloned().unwrap_or_default()),
+        );
+        row
+    }
+}
+
+/// Task with its subtasks, only the ids are given as the tasks are in the flat list already
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TaskNode {
+    pub task_id: u32,
+    pub subtasks: Vec<TaskNode>,
+}
+
+/// Serialized with the `tree` of its tasks next to the flat list, the tree is derived from the
+/// parent ids and ignored when a list is read back
+#[derive(Debug, Deserialize, Default)]
 pub struct TodoList {
     tasks: Vec<Task>,
+    /// Columns of the file this version does not know, in the order they were read
+    #[serde(default)]
+    extra_columns: Vec<String>,
+    /// Set for version 1 files, which have no version line, so task lines start one earlier
+    #[serde(skip)]
+    without_version_line: bool,
 }
 
+impl TodoList {
+    /// Lists from other formats go through the same repair of the hierarchy as read ones
+    pub fn new(tasks: Vec<Task>) -> Self {
+        let mut todo_list = Self {
+            tasks,
+            extra_columns: Vec::new(),
+            without_version_line: false,
+        };
+        todo_list.repair_hierarchy();
+        todo_list
+    }
+
+    pub fn get_tasks(&self) -> &Vec<Task> {
+        &self.tasks
+    }
+
+    pub fn get_tasks_mut(&mut self) -> &mut Vec<Task> {
+        &mut self.tasks
+    }
+
+    fn position(&self, task_id: u32) -> Result<usize> {
+        self.tasks
+            .iter()
+            .position(|task| task.task_id == task_id)
+            .ok_or_else(|| anyhow!("Task {} does not exist", task_id))
+    }
+
+    pub fn get_task(&self, task_id: u32) -> Result<&Task> {
+        Ok(&self.tasks[self.position(task_id)?])
+    }
+
+    fn next_task_id(&self) -> u32 {
+        self.tasks
+            .iter()
+            .map(|task| task.task_id)
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+
+    /// Ids of the subtasks of the task, their subtasks and so on
+    pub fn get_descendants(&self, task_id: u32) -> Vec<u32> {
+        let mut descendants = Vec::new();
+        let mut parents = vec![task_id];
+
+        while let Some(parent_id) = parents.pop() {
+            for task in &self.tasks {
+                if task.parent_id == Some(parent_id) && !descendants.contains(&task.task_id) {
+                    descendants.push(task.task_id);
+                    parents.push(task.task_id);
+                }
+            }
+        }
+        descendants
+    }
+
+    /// Adds the task at `index`, or at the end if there is no index, returns its new id
+    pub fn add_task(&mut self, mut task: Task, index: Option<usize>) -> Result<u32> {
+        if let Some(parent_id) = task.parent_id {
+            self.position(parent_id)?;
+        }
+        let index = index.unwrap_or(self.tasks.len());
+        if index > self.tasks.len() {
+            return Err(anyhow!("Position {} is out of the list", index));
+        }
+
+        task.task_id = self.next_task_id();
+        task.created_at = Utc::now();
+        task.completed_at = task.is_completed.then(Utc::now);
+        let task_id = task.task_id;
+        self.tasks.insert(index, task);
+        Ok(task_id)
+    }
+
+    /// Replaces the task with the same id, the creation and completion times are not taken
+    /// from the update
+    pub fn update_task(&mut self, task: Task) -> Result<()> {
+        let index = self.position(task.task_id)?;
+        self.check_parent(task.task_id, task.parent_id)?;
+
+        let old = &self.tasks[index];
+        let completed = task.is_completed;
+        let task = Task {
+            is_completed: old.is_completed,
+            created_at: old.created_at,
+            completed_at: old.completed_at,
+            ..task
+        };
+        self.tasks[index] = task;
+        self.complete_at(index, completed);
+        Ok(())
+    }
+
+    /// Completing a repeating task adds its next occurrence right after it, the completed task
+    /// stays as history and does not repeat anymore
+    fn complete_at(&mut self, index: usize, completed: bool) {
+        if self.tasks[index].is_completed == completed {
+            return;
+        }
+        self.tasks[index].set_completed(completed);
+        if !completed {
+            return;
+        }
+
+        let next_task_id = self.next_task_id();
+        if let Some(next) = self.tasks[index].get_next_occurrence(next_task_id) {
+            self.tasks[index].repeat = None;
+            self.tasks.insert(index + 1, next);
+        }
+    }
+
+    /// With `cascade` the subtasks are completed or uncompleted along with the task
+    pub fn set_completed(&mut self, task_id: u32, completed: bool, cascade: bool) -> Result<()> {
+        let mut task_ids = vec![task_id];
+        if cascade {
+            task_ids.extend(self.get_descendants(task_id));
+        }
+
+        for task_id in task_ids {
+            let index = self.position(task_id)?;
+            self.complete_at(index, completed);
+        }
+        Ok(())
+    }
+
+    /// Deletes the task together with its subtasks
+    pub fn delete_task(&mut self, task_id: u32) -> Result<()> {
+        self.position(task_id)?;
+        let mut deleted = self.get_descendants(task_id);
+        deleted.push(task_id);
+        self.tasks.retain(|task| !deleted.contains(&task.task_id));
+        Ok(())
+    }
+
+    pub fn move_task(&mut self, task_id: u32, index: usize) -> Result<()> {
+        let from = self.position(task_id)?;
+        if index >= self.tasks.len() {
+            return Err(anyhow!("Position {} is out of the list", index));
+        }
+        let task = self.tasks.remove(from);
+        self.tasks.insert(index, task);
+        Ok(())
+    }
+
+    /// The parent has to be in the list and can not be the task or one of its subtasks
+    fn check_parent(&self, task_id: u32, parent_id: Option<u32>) -> Result<()> {
+        let parent_id = match parent_id {
+            Some(parent_id) => parent_id,
+            None => return Ok(()),
+        };
+        self.position(parent_id)?;
+        if parent_id == task_id || self.get_descendants(task_id).contains(&parent_id) {
+            return Err(anyhow!(
+                "Task {} can not be a subtask of its own subtask",
+                task_id
+            ));
+        }
+        Ok(())
+    }
+
+    /// Moves the task with its subtasks under the parent, or to the top level without one.
+    /// `index` counts the subtasks of the parent, the task becomes the last one without it.
+    /// The moved tasks keep their ids and their order.
+    pub fn move_subtree(
+        &mut self,
+        task_id: u32,
+        parent_id: Option<u32>,
+        index: Option<usize>,
+    ) -> Result<()> {
+        self.position(task_id)?;
+        self.check_parent(task_id, parent_id)?;
+
+        let mut moved_ids = self.get_descendants(task_id);
+        moved_ids.push(task_id);
+        let (mut moved, rest): (Vec<Task>, Vec<Task>) = std::mem::take(&mut self.tasks)
+            .into_iter()
+            .partition(|task| moved_ids.contains(&task.task_id));
+        self.tasks = rest;
+
+        let siblings: Vec<usize> = (0..self.tasks.len())
+            .filter(|i| self.tasks[*i].parent_id == parent_id)
+            .collect();
+        let position = match (index.and_then(|index| siblings.get(index)), parent_id) {
+            (Some(sibling), _) => *sibling,
+            // after the parent and everything under it
+            (None, Some(parent_id)) => {
+                let mut subtree = self.get_descendants(parent_id);
+                subtree.push(parent_id);
+                self.tasks
+                    .iter()
+                    .rposition(|task| subtree.contains(&task.task_id))
+                    .map_or(self.tasks.len(), |last| last + 1)
+            }
+            (None, None) => self.tasks.len(),
+        };
+
+        if let Some(task) = moved.iter_mut().find(|task| task.task_id == task_id) {
+            task.parent_id = parent_id;
+        }
+        self.tasks.splice(position..position, moved);
+        Ok(())
+    }
+
+    fn get_siblings(&self, task_id: u32) -> Result<Vec<u32>> {
+        let parent_id = self.get_task(task_id)?.parent_id;
+        Ok(self
+            .tasks
+            .iter()
+            .filter(|task| task.parent_id == parent_id)
+            .map(|task| task.task_id)
+            .collect())
+    }
+
+    /// Makes the task the last subtask of the sibling before it
+    pub fn indent_task(&mut self, task_id: u32) -> Result<()> {
+        let siblings = self.get_siblings(task_id)?;
+        let previous = siblings
+            .iter()
+            .position(|id| *id == task_id)
+            .and_then(|i| i.checked_sub(1))
+            .map(|i| siblings[i])
+            .ok_or_else(|| anyhow!("Task {} has no task before it to indent under", task_id))?;
+        self.move_subtree(task_id, Some(previous), None)
+    }
+
+    /// Makes the task a sibling of its parent, right after it
+    pub fn outdent_task(&mut self, task_id: u32) -> Result<()> {
+        let parent_id = self
+            .get_task(task_id)?
+            .parent_id
+            .ok_or_else(|| anyhow!("Task {} is not a subtask", task_id))?;
+        let grandparent_id = self.get_task(parent_id)?.parent_id;
+
+        let siblings = self.get_siblings(parent_id)?;
+        let index = siblings
+            .iter()
+            .position(|id| *id == parent_id)
+            .map(|i| i + 1);
+        self.move_subtree(task_id, grandparent_id, index)
+    }
+
+    /// Parent of every task, to walk up the hierarchy
+    fn get_parents(&self) -> HashMap<u32, Option<u32>> {
+        self.tasks
+            .iter()
+            .map(|task| (task.task_id, task.parent_id))
+            .collect()
+    }
+
+    /// Whether walking up from the task leads back to it
+    fn is_in_cycle(parents: &HashMap<u32, Option<u32>>, task_id: u32) -> bool {
+        let mut current = parents.get(&task_id).copied().flatten();
+        // a cycle the task is not part of would be walked forever
+        for _ in 0..parents.len() {
+            match current {
+                Some(id) if id == task_id => return true,
+                Some(id) => current = parents.get(&id).copied().flatten(),
+                None => return false,
+            }
+        }
+        false
+    }
+
+    /// Task ids have to be unique and every parent has to be in the list without the tasks
+    /// being nested in themselves
+    pub fn validate(&self) -> Result<()> {
+        let parents = self.get_parents();
+        if parents.len() != self.tasks.len() {
+            return Err(anyhow!("Task ids are not unique"));
+        }
+
+        for task in &self.tasks {
+            if let Some(parent_id) = task.parent_id {
+                if !parents.contains_key(&parent_id) {
+                    return Err(anyhow!(
+                        "Parent {} of task {} does not exist",
+                        parent_id,
+                        task.task_id
+                    ));
+                }
+            }
+            if Self::is_in_cycle(&parents, task.task_id) {
+                return Err(anyhow!("Task {} is a subtask of itself", task.task_id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Lists written by hand or other apps may have broken parents, tasks with a missing
+    /// parent go to the top level and a cycle is broken at its first task in the list
+    fn repair_hierarchy(&mut self) {
+        let mut parents = self.get_parents();
+        for task in self.tasks.iter_mut() {
+            let dangling = task
+                .parent_id
+                .is_some_and(|parent_id| !parents.contains_key(&parent_id));
+            if dangling || Self::is_in_cycle(&parents, task.task_id) {
+                task.parent_id = None;
+                parents.insert(task.task_id, None);
+            }
+        }
+    }
+
+    fn get_subtree(&self, task_id: u32, depth: usize) -> TaskNode {
+        let subtasks = match depth < self.tasks.len() {
+            true => self
+                .tasks
+                .iter()
+                .filter(|task| task.parent_id == Some(task_id))
+                .map(|task| self.get_subtree(task.task_id, depth + 1))
+                .collect(),
+            false => Vec::new(),
+        };
+        TaskNode { task_id, subtasks }
+    }
+
+    /// Top level tasks with their subtasks, in the order of the list
+    pub fn get_tree(&self) -> Vec<TaskNode> {
+        self.tasks
+            .iter()
+            .filter(|task| task.parent_id.is_none())
+            .map(|task| self.get_subtree(task.task_id, 0))
+            .collect()
+    }
+
+    /// Line of the read or serialized list every task starts on, after the version line, if the
+    /// list has one, and the header. Values with line breaks make a task span several lines.
+    pub fn get_task_lines(&self) -> Vec<usize> {
+        let mut line = if self.without_version_line { 2 } else { 3 };
+        self.tasks
+            .iter()
+            .map(|task| {
+                let start = line;
+                line += 1 + task
+                    .to_row(&self.extra_columns)
+                    .iter()
+                    .map(|value| value.matches('\n').count())
+                    .sum::<usize>();
+                start
+            })
+            .collect()
+    }
+
+    /// Reads the version line, lists without one are version 1
+    fn read_version(file_content: &str) -> Result<(u32, &str)> {
+        let first_line = file_content.lines().next().unwrap_or("");
+        let version = match first_line.trim().strip_prefix(VERSION_PREFIX) {
+            Some(version) => version.trim().parse()?,
+            None => return Ok((1, file_content)),
+        };
+
+        if version > TODO_LIST_VERSION {
+            return Err(anyhow!(
+                "Todo list was written by a newer version of Noter (v{})",
+                version
+            ));
+        }
+        let rest = file_content
+            .split_once('\n')
+            .map(|(_, rest)| rest)
+            .unwrap_or("");
+        Ok((version, rest))
+    }
+}
+
+impl Serialize for TodoList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("TodoList", 3)?;
+        state.serialize_field("tasks", &self.tasks)?;
+        state.serialize_field("extra_columns", &self.extra_columns)?;
+        state.serialize_field("tree", &self.get_tree())?;
+        state.end()
+    }
+}
 
 impl FileSerializable for TodoList {
     fn custom_deserialize(file_content: &[u8]) -> Result<Box<Self>> {
-        let mut rdr = csv::Reader::from_reader(file_content);
-        let mut tasks = Vec::new();
+        let file_content = String::from_utf8_lossy(file_content);
+        let (version, content) = Self::read_version(&file_content)?;
 
-        for result in rdr.deserialize() {
-            let task: Task = result?;
-            tasks.push(task);
+        let mut rdr = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(content.as_bytes());
+        let headers: Vec<String> = rdr
+            .headers()?
+            .iter()
+            .map(|h| h.trim().to_string())
+            .collect();
+
+        let mut tasks = Vec::new();
+        for record in rdr.records() {
+            let record = record?;
+            if record.iter().all(|cell| cell.trim().is_empty()) {
+                continue;
+            }
+            let row = headers
+                .iter()
+                .cloned()
+                .zip(record.iter().map(|cell| cell.to_string()))
+                .collect();
+            tasks.push(Task::from_row(row));
         }
 
-        Ok(Box::new(TodoList { tasks }))
+        // rows without an id, or with an id used already, get a fresh one
+        let mut next_id = tasks.iter().filter_map(|(id, _)| *id).max().unwrap_or(0) + 1;
+        let mut used = HashSet::new();
+        let tasks = tasks
+            .into_iter()
+            .map(|(id, mut task)| {
+                if id.is_none() || !used.insert(task.task_id) {
+                    task.task_id = next_id;
+                    next_id += 1;
+                }
+                task
+            })
+            .collect();
+
+        let extra_columns = headers
+            .into_iter()
+            .filter(|header| !header.is_empty() && !COLUMNS.contains(&header.as_str()))
+            .collect();
+
+        let mut todo_list = TodoList {
+            tasks,
+            extra_columns,
+            without_version_line: version == 1,
+        };
+        todo_list.repair_hierarchy();
+        Ok(Box::new(todo_list))
     }
 
     fn custom_serialize(&self) -> Result<Vec<u8>> {
-        let mut wtr = csv::Writer::from_writer(Vec::new());
+        self.validate()?;
+        let mut content = format!("{}{}\n", VERSION_PREFIX, TODO_LIST_VERSION).into_bytes();
+        let mut wtr = csv::Writer::from_writer(&mut content);
 
+        wtr.write_record(
+            COLUMNS
+                .iter()
+                .copied()
+                .chain(self.extra_columns.iter().map(|column| column.as_str())),
+        )?;
         for task in &self.tasks {
-            wtr.serialize(task)?;
+            wtr.write_record(task.to_row(&self.extra_columns))?;
         }
 
         wtr.flush()?;
+        drop(wtr);
+
+        Ok(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tolerant_read() {
+        let csv = "content,due_date,estimate,task_id\nBuy milk,2024-03-01,1h,\nCall mom,,,7\n,,,\nPay rent,not a date,,7\n";
+        let list = TodoList::custom_deserialize(csv.as_bytes()).unwrap();
+        let tasks = list.get_tasks();
+
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].get_content(), "Buy milk");
+        assert_eq!(
+            Task::format_date(&tasks[0].due_date),
+            "2024-03-01T00:00:00Z"
+        );
+        assert!(tasks[0].attachment.is_none());
+        assert_eq!(tasks[1].due_date, None);
+        // missing and duplicate ids are replaced by unique ones
+        let ids: Vec<u32> = tasks.iter().map(|task| task.get_task_id()).collect();
+        assert_eq!(ids, vec![8, 7, 9]);
+        assert_eq!(list.extra_columns, vec!["estimate"]);
+        // version 1 lists have no version line, the header is the first line
+        assert_eq!(list.get_task_lines(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let csv = "task_id,content,estimate\n1,\"Write, review\",2h\n";
+        let list = TodoList::custom_deserialize(csv.as_bytes()).unwrap();
+        let written = String::from_utf8(list.custom_serialize().unwrap()).unwrap();
+        assert!(written.starts_with("# noter todo list v3\ntask_id,parent_id,"));
+        assert!(written.contains(",tags,estimate\n1,,Task,\"Write, review\","));
+
+        let list = TodoList::custom_deserialize(written.as_bytes()).unwrap();
+        assert_eq!(list.get_task_lines(), vec![3]);
+        assert_eq!(list.get_tasks()[0].get_content(), "Write, review");
+        assert_eq!(list.get_tasks()[0].extra["estimate"], "2h");
+        assert_eq!(
+            String::from_utf8(list.custom_serialize().unwrap()).unwrap(),
+            written
+        );
+
+        assert!(TodoList::custom_deserialize(b"# noter todo list v99\ntask_id\n").is_err());
+    }
+
+    #[test]
+    fn test_edit_tasks() {
+        let mut list = TodoList::default();
+        let parent = list
+            .add_task(Task::new(0, "Move".to_string()), None)
+            .unwrap();
+        let child = child_of(&mut list, parent, "Pack");
+        child_of(&mut list, child, "Tape");
+        list.add_task(Task::new(0, "Rest".to_string()), Some(0))
+            .unwrap();
+
+        list.set_completed(parent, true, true).unwrap();
+        let completed: Vec<bool> = list.get_tasks().iter().map(|t| t.is_completed()).collect();
+        assert_eq!(completed, vec![false, true, true, true]);
+        assert!(list.get_task(child).unwrap().completed_at.is_some());
+
+        let mut update = list.get_task(child).unwrap().clone();
+        update.content = "Pack boxes".to_string();
+        update.is_completed = false;
+        list.update_task(update).unwrap();
+        assert!(list.get_task(child).unwrap().completed_at.is_none());
+        assert!(list.get_task(parent).unwrap().is_completed());
+
+        list.move_task(parent, 3).unwrap();
+        assert_eq!(list.get_tasks()[3].get_content(), "Move");
+        list.delete_task(child).unwrap();
+        assert_eq!(list.get_tasks().len(), 2);
+    }
+
+    #[test]
+    fn test_repeat() {
+        let mut list = TodoList::default();
+        let mut task = Task::new(0, "Water plants".to_string());
+        task.due_date = Task::parse_date("2024-01-31T18:00:00Z");
+        task.reminder = Task::parse_date("2024-01-31T17:00:00Z");
+        task.repeat = Some(Repeat::Monthly);
+        let task_id = list.add_task(task, None).unwrap();
+
+        list.set_completed(task_id, true, false).unwrap();
+        let tasks = list.get_tasks();
+        assert_eq!(tasks.len(), 2);
+        assert!(tasks[0].is_completed() && tasks[0].repeat.is_none());
+        assert!(!tasks[1].is_completed());
+        assert_eq!(
+            Task::format_date(&tasks[1].due_date),
+            "2024-02-29T18:00:00Z"
+        );
+        assert_eq!(
+            Task::format_date(&tasks[1].reminder),
+            "2024-02-29T17:00:00Z"
+        );
+
+        // completing the history again does not add another occurrence
+        list.set_completed(task_id, false, false).unwrap();
+        list.set_completed(task_id, true, false).unwrap();
+        assert_eq!(list.get_tasks().len(), 2);
+    }
+
+    #[test]
+    fn test_hierarchy() {
+        // 3 is under a missing task, 4 and 5 are under each other
+        let csv = "task_id,parent_id,content\n1,,Move\n2,1,Pack\n3,9,Tape\n4,5,Sell\n5,4,Clean\n";
+        let mut list = *TodoList::custom_deserialize(csv.as_bytes()).unwrap();
+        let parents: Vec<Option<u32>> = list.get_tasks().iter().map(|t| t.parent_id).collect();
+        assert_eq!(parents, vec![None, Some(1), None, None, Some(4)]);
+        list.validate().unwrap();
+
+        let node = |task_id: u32, subtasks: Vec<TaskNode>| TaskNode { task_id, subtasks };
+        assert_eq!(
+            list.get_tree(),
+            vec![
+                node(1, vec![node(2, vec![])]),
+                node(3, vec![]),
+                node(4, vec![node(5, vec![])])
+            ]
+        );
+
+        list.indent_task(3).unwrap();
+        assert_eq!(list.get_task(3).unwrap().parent_id, Some(1));
+        assert!(list.indent_task(2).is_err());
+        list.indent_task(3).unwrap();
+        assert_eq!(list.get_task(3).unwrap().parent_id, Some(2));
+
+        // the subtree of 2 comes right after its old parent
+        list.outdent_task(2).unwrap();
+        let ids: Vec<u32> = list.get_tasks().iter().map(|t| t.task_id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+        assert_eq!(list.get_task(2).unwrap().parent_id, None);
+        assert!(list.outdent_task(1).is_err());
+
+        // the subtree moves in front of 5, under 4
+        list.move_subtree(2, Some(4), Some(0)).unwrap();
+        let ids: Vec<u32> = list.get_tasks().iter().map(|t| t.task_id).collect();
+        assert_eq!(ids, vec![1, 4, 2, 3, 5]);
+        assert!(list.move_subtree(4, Some(3), None).is_err());
+
+        let mut update = list.get_task(4).unwrap().clone();
+        update.parent_id = Some(2);
+        assert!(list.update_task(update).is_err());
+
+        list.get_tasks_mut()[0].parent_id = Some(42);
+        assert!(list.custom_serialize().is_err());
+    }
 
-        Ok(wtr.into_inner()?)
+    fn child_of(list: &mut TodoList, parent: u32, content: &str) -> u32 {
+        let mut task = Task::new(0, content.to_string());
+        task.parent_id = Some(parent);
+        list.add_task(task, None).unwrap()
     }
 }
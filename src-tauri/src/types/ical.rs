@@ -0,0 +1,573 @@
+use anyhow::{anyhow, Result};
+use chrono::{
+    DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday,
+};
+use std::collections::HashMap;
+
+use super::{Id, Repeat, Task, TodoList};
+
+const PRODID: &str = "-//Noter//Noter//EN";
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const MAX_LINE_LENGTH: usize = 75;
+
+const PROJECT_PROPERTY: &str = "X-NOTER-PROJECT";
+
+/// Property of a calendar component, `DUE;VALUE=DATE:20240501` has the name `DUE`, one param
+/// and the value `20240501`
+#[derive(Debug)]
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn parse(line: &str) -> Option<Self> {
+        // the value starts after the first colon outside of a quoted param value
+        let mut quoted = false;
+        let split = line.char_indices().find(|(_, c)| {
+            if *c == '"' {
+                quoted = !quoted;
+            }
+            *c == ':' && !quoted
+        })?;
+        let (head, value) = (&line[..split.0], &line[split.0 + 1..]);
+
+        let mut parts = head.split(';');
+        let name = parts.next()?.trim().to_uppercase();
+        let params = parts
+            .filter_map(|param| param.split_once('='))
+            .map(|(key, value)| (key.to_uppercase(), value.trim_matches('"').to_string()))
+            .collect();
+
+        Some(Self {
+            name,
+            params,
+            value: value.to_string(),
+        })
+    }
+
+    fn get_param(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Component with its own properties and the components nested in it
+#[derive(Debug, Default)]
+struct Component {
+    name: String,
+    properties: Vec<Property>,
+    children: Vec<Component>,
+}
+
+impl Component {
+    fn get(&self, name: &str) -> Option<&Property> {
+        self.properties
+            .iter()
+            .find(|property| property.name == name)
+    }
+
+    fn get_text(&self, name: &str) -> Option<String> {
+        self.get(name).map(|property| unescape(&property.value))
+    }
+
+    fn get_date(&self, name: &str) -> Option<DateTime<Utc>> {
+        self.get(name).and_then(parse_date)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(escaped) => unescaped.push(escaped),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+/// Lines longer than 75 bytes are folded, continuation lines start with a space
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn format_date(date: &DateTime<Utc>) -> String {
+    date.format(DATE_TIME_FORMAT).to_string()
+}
+
+/// Dates without a time are stored as UTC midnight, they are written as whole days so they
+/// stay on their day in every time zone
+fn format_date_property(name: &str, date: &DateTime<Utc>) -> String {
+    match date.time() == NaiveTime::MIN {
+        true => format!("{};VALUE=DATE:{}", name, date.format("%Y%m%d")),
+        false => format!("{}:{}", name, format_date(date)),
+    }
+}
+
+/// Dates in utc and dates without a time zone are read, times in a named time zone are taken
+/// as local time, as time zone definitions are not resolved
+fn parse_date(property: &Property) -> Option<DateTime<Utc>> {
+    let value = property.value.trim();
+    if property.get_param("VALUE") == Some("DATE") || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+    }
+
+    if let Some(value) = value.strip_suffix('Z') {
+        let date = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+        return Some(date.and_utc());
+    }
+    let date = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Local
+        .from_local_datetime(&date)
+        .earliest()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Durations like `-PT15M` or `P1DT2H`, weeks, days, hours, minutes and seconds are read
+fn parse_duration(value: &str) -> Option<Duration> {
+    let (sign, value) = match value.trim().strip_prefix('-') {
+        Some(value) => (-1, value),
+        None => (1, value.trim().trim_start_matches('+')),
+    };
+    let value = value.strip_prefix('P')?;
+
+    let mut duration = Duration::zero();
+    let mut number = String::new();
+    for c in value.chars() {
+        match c {
+            'T' => {}
+            c if c.is_ascii_digit() => number.push(c),
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                duration += match unit {
+                    'W' => Duration::weeks(n),
+                    'D' => Duration::days(n),
+                    'H' => Duration::hours(n),
+                    'M' => Duration::minutes(n),
+                    'S' => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(duration * sign)
+}
+
+/// Noter priorities count up with importance, iCalendar ones count down from 1 as the highest
+/// to 9 as the lowest, 0 means no priority in both. The mapping works in both directions.
+fn convert_priority(priority: u8) -> u8 {
+    match priority {
+        0 => 0,
+        priority => 10 - priority.min(9),
+    }
+}
+
+fn weekday_code(weekday: &Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn format_rrule(task: &Task) -> Option<String> {
+    let frequency = match task.get_repeat()? {
+        Repeat::Daily => "DAILY",
+        Repeat::Weekly => "WEEKLY",
+        Repeat::Monthly => "MONTHLY",
+        Repeat::Yearly => "YEARLY",
+    };
+
+    let mut rule = format!("FREQ={}", frequency);
+    if task.get_repeat_interval() > 1 {
+        rule.push_str(&format!(";INTERVAL={}", task.get_repeat_interval()));
+    }
+    if !task.get_repeat_weekdays().is_empty() {
+        let days: Vec<&str> = task
+            .get_repeat_weekdays()
+            .iter()
+            .map(weekday_code)
+            .collect();
+        rule.push_str(&format!(";BYDAY={}", days.join(",")));
+    }
+    Some(rule)
+}
+
+fn parse_rrule(task: &mut Task, rule: &str) {
+    let mut repeat = None;
+    let mut interval = 1;
+    let mut weekdays = Vec::new();
+    for part in rule.split(';') {
+        let (key, value) = match part.split_once('=') {
+            Some(part) => part,
+            None => continue,
+        };
+        match key.to_uppercase().as_str() {
+            // the frequencies share their names with the repeats
+            "FREQ" => repeat = Repeat::from_name(value),
+            "INTERVAL" => interval = value.parse().unwrap_or(1),
+            "BYDAY" => {
+                weekdays = value
+                    .split(',')
+                    // ordinals like `1MO` are dropped, they have no counterpart
+                    .map(|day| day.trim_start_matches(|c: char| !c.is_ascii_alphabetic()))
+                    .filter_map(|day| match day.to_uppercase().as_str() {
+                        "MO" => Some(Weekday::Mon),
+                        "TU" => Some(Weekday::Tue),
+                        "WE" => Some(Weekday::Wed),
+                        "TH" => Some(Weekday::Thu),
+                        "FR" => Some(Weekday::Fri),
+                        "SA" => Some(Weekday::Sat),
+                        "SU" => Some(Weekday::Sun),
+                        _ => None,
+                    })
+                    .collect()
+            }
+            _ => {}
+        }
+    }
+    task.set_repeat(repeat, interval, weekdays);
+}
+
+fn get_uid(source: &Id, task_id: u32) -> String {
+    format!("{}-{}@noter", task_id, source.as_str())
+}
+
+fn write_task(lines: &mut Vec<String>, source: &Id, task: &Task) {
+    lines.push("BEGIN:VTODO".to_string());
+    let uid = get_uid(source, task.get_task_id());
+    lines.push(format!("UID:{}", escape(&uid)));
+    lines.push(format!("DTSTAMP:{}", format_date(&task.get_created_at())));
+    lines.push(format!("CREATED:{}", format_date(&task.get_created_at())));
+    lines.push(format!("SUMMARY:{}", escape(task.get_content())));
+    if !task.get_description().is_empty() {
+        lines.push(format!("DESCRIPTION:{}", escape(task.get_description())));
+    }
+    if let Some(start_date) = task.get_start_date() {
+        lines.push(format_date_property("DTSTART", &start_date));
+    }
+    if let Some(due_date) = task.get_due_date() {
+        lines.push(format_date_property("DUE", &due_date));
+    }
+    if task.get_priority() > 0 {
+        lines.push(format!(
+            "PRIORITY:{}",
+            convert_priority(task.get_priority())
+        ));
+    }
+    match task.is_completed() {
+        true => {
+            lines.push("STATUS:COMPLETED".to_string());
+            if let Some(completed_at) = task.get_completed_at() {
+                lines.push(format!("COMPLETED:{}", format_date(&completed_at)));
+            }
+        }
+        false => lines.push("STATUS:NEEDS-ACTION".to_string()),
+    }
+    if let Some(rule) = format_rrule(task) {
+        lines.push(format!("RRULE:{}", rule));
+    }
+    if let Some(parent_id) = task.get_parent_id() {
+        lines.push(format!(
+            "RELATED-TO;RELTYPE=PARENT:{}",
+            escape(&get_uid(source, parent_id))
+        ));
+    }
+    let tags = task.get_tags();
+    if !tags.is_empty() {
+        let tags: Vec<String> = tags.iter().map(|tag| escape(tag)).collect();
+        lines.push(format!("CATEGORIES:{}", tags.join(",")));
+    }
+    if !task.get_project().is_empty() {
+        lines.push(format!(
+            "{}:{}",
+            PROJECT_PROPERTY,
+            escape(task.get_project())
+        ));
+    }
+    if let Some(reminder) = task.get_reminder() {
+        lines.push("BEGIN:VALARM".to_string());
+        lines.push("ACTION:DISPLAY".to_string());
+        lines.push(format!("DESCRIPTION:{}", escape(task.get_content())));
+        lines.push(format!(
+            "TRIGGER;VALUE=DATE-TIME:{}",
+            format_date(&reminder)
+        ));
+        lines.push("END:VALARM".to_string());
+    }
+    lines.push("END:VTODO".to_string());
+}
+
+/// Writes the tasks of the todo lists as VTODO entries of one calendar
+pub fn export_todo_lists(lists: &[(Id, TodoList)]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+    ];
+    for (source, todo_list) in lists {
+        for task in todo_list.get_tasks() {
+            write_task(&mut lines, source, task);
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+fn parse_components(content: &str) -> Result<Vec<Component>> {
+    let mut stack: Vec<Component> = vec![Component::default()];
+
+    for line in unfold(content) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let property = match Property::parse(&line) {
+            Some(property) => property,
+            None => continue,
+        };
+
+        match property.name.as_str() {
+            "BEGIN" => stack.push(Component {
+                name: property.value.trim().to_uppercase(),
+                ..Component::default()
+            }),
+            "END" => {
+                if stack.len() < 2 {
+                    return Err(anyhow!("Unexpected END:{}", property.value));
+                }
+                let component = stack.pop().unwrap_or_default();
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(component);
+                }
+            }
+            _ => {
+                if let Some(component) = stack.last_mut() {
+                    component.properties.push(property);
+                }
+            }
+        }
+    }
+
+    match stack.pop() {
+        Some(root) if stack.is_empty() => Ok(root.children),
+        _ => Err(anyhow!("Calendar is not closed")),
+    }
+}
+
+fn read_task(component: &Component, task_id: u32) -> Task {
+    let mut task = Task::new(task_id, component.get_text("SUMMARY").unwrap_or_default());
+    task.set_description(component.get_text("DESCRIPTION").unwrap_or_default());
+    task.set_project(component.get_text(PROJECT_PROPERTY).unwrap_or_default());
+    let start_date = component.get_date("DTSTART");
+    let due_date = match component.name.as_str() {
+        // an event is due when it starts
+        "VEVENT" => start_date,
+        _ => component.get_date("DUE"),
+    };
+    task.set_start_date(start_date);
+    task.set_due_date(due_date);
+    if let Some(created_at) = component
+        .get_date("CREATED")
+        .or_else(|| component.get_date("DTSTAMP"))
+    {
+        task.set_created_at(created_at);
+    }
+    task.set_priority(
+        component
+            .get("PRIORITY")
+            .and_then(|priority| priority.value.trim().parse().ok())
+            .map(convert_priority)
+            .unwrap_or(0),
+    );
+
+    let status = component.get("STATUS").map(|status| status.value.trim());
+    let is_completed = status == Some("COMPLETED") || component.get("COMPLETED").is_some();
+    task.set_completion(is_completed, component.get_date("COMPLETED"));
+
+    if let Some(rule) = component.get("RRULE") {
+        parse_rrule(&mut task, &rule.value);
+    }
+    let tags: Vec<String> = component
+        .properties
+        .iter()
+        .filter(|property| property.name == "CATEGORIES")
+        .flat_map(|property| property.value.split(',').map(unescape))
+        .map(|tag| tag.trim().replace(' ', "-"))
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    task.set_tags(tags);
+
+    let reminder = component
+        .children
+        .iter()
+        .filter(|alarm| alarm.name == "VALARM")
+        .filter_map(|alarm| {
+            let trigger = alarm.get("TRIGGER")?;
+            if trigger.get_param("VALUE") == Some("DATE-TIME") {
+                return parse_date(trigger);
+            }
+            let base = match trigger.get_param("RELATED") {
+                Some("END") => due_date,
+                _ => start_date.or(due_date),
+            };
+            Some(base? + parse_duration(&trigger.value)?)
+        })
+        .min();
+    task.set_reminder(reminder);
+
+    task
+}
+
+/// Reads the VTODO and VEVENT entries of a calendar into a new todo list. Subtasks are kept
+/// when their parent is in the same calendar.
+pub fn import_todo_list(content: &str) -> Result<TodoList> {
+    let calendars = parse_components(content)?;
+    let components: Vec<&Component> = calendars
+        .iter()
+        .filter(|calendar| calendar.name == "VCALENDAR")
+        .flat_map(|calendar| calendar.children.iter())
+        .filter(|component| component.name == "VTODO" || component.name == "VEVENT")
+        .collect();
+
+    let uids: HashMap<String, u32> = components
+        .iter()
+        .enumerate()
+        .filter_map(|(i, component)| Some((component.get_text("UID")?, i as u32 + 1)))
+        .collect();
+
+    let tasks = components
+        .iter()
+        .enumerate()
+        .map(|(i, component)| {
+            let task_id = i as u32 + 1;
+            let mut task = read_task(component, task_id);
+            let parent_id = component
+                .properties
+                .iter()
+                .filter(|property| property.name == "RELATED-TO")
+                .filter(|property| {
+                    property
+                        .get_param("RELTYPE")
+                        .is_none_or(|reltype| reltype.eq_ignore_ascii_case("PARENT"))
+                })
+                .find_map(|property| uids.get(&unescape(&property.value)).copied())
+                .filter(|parent_id| *parent_id != task_id);
+            task.set_parent_id(parent_id);
+            task
+        })
+        .collect();
+
+    Ok(TodoList::new(tasks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FileSerializable;
+
+    #[test]
+    fn test_round_trip() {
+        let csv = "task_id,parent_id,content,description,priority,due_date,reminder,repeat,repeat_interval,repeat_weekdays,tags,is_completed\n\
+            1,,\"Plan trip; book hotel, flights\",\"Line one\nLine two\",3,2024-05-10T12:00:00Z,2024-05-10T11:00:00Z,Weekly,2,Mon Fri,travel,false\n\
+            2,1,Pack,,0,2024-05-09,,,,,,true\n";
+        let todo_list = *TodoList::custom_deserialize(csv.as_bytes()).unwrap();
+        let source = Id::from_string("trips/plan.todo.csv".to_string());
+
+        let ics = export_todo_lists(&[(source, todo_list)]);
+        assert!(ics.contains("SUMMARY:Plan trip\\; book hotel\\, flights\r\n"));
+        assert!(ics.contains("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR\r\n"));
+        assert!(ics.contains("PRIORITY:7\r\n"));
+        assert!(ics.contains("DUE:20240510T120000Z\r\n"));
+        assert!(ics.contains("DUE;VALUE=DATE:20240509\r\n"));
+        assert!(ics.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+
+        let imported = import_todo_list(&ics).unwrap();
+        let tasks = imported.get_tasks();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].get_content(), "Plan trip; book hotel, flights");
+        assert_eq!(tasks[0].get_description(), "Line one\nLine two");
+        assert_eq!(tasks[0].get_priority(), 3);
+        assert_eq!(tasks[0].get_repeat(), Some(Repeat::Weekly));
+        assert_eq!(tasks[0].get_repeat_interval(), 2);
+        assert_eq!(
+            tasks[0].get_repeat_weekdays(),
+            &vec![Weekday::Mon, Weekday::Fri]
+        );
+        assert_eq!(
+            tasks[0].get_reminder(),
+            tasks[0].get_due_date().map(|d| d - Duration::hours(1))
+        );
+        assert_eq!(tasks[0].get_tags(), vec!["travel"]);
+        assert_eq!(tasks[1].get_parent_id(), Some(1));
+        assert_eq!(
+            tasks[1].get_due_date().map(|date| date.to_rfc3339()),
+            Some("2024-05-09T00:00:00+00:00".to_string())
+        );
+        assert!(tasks[1].is_completed());
+    }
+
+    #[test]
+    fn test_import_event() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nSUMMARY:Dentist appoint\r\n ment\r\nDTSTART;VALUE=DATE:20240601\r\nBEGIN:VALARM\r\nTRIGGER:-P1D\r\nEND:VALARM\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let imported = import_todo_list(ics).unwrap();
+        let task = &imported.get_tasks()[0];
+        assert_eq!(task.get_content(), "Dentist appointment");
+        assert_eq!(
+            task.get_due_date().map(|date| date.to_rfc3339()),
+            Some("2024-06-01T00:00:00+00:00".to_string())
+        );
+        assert_eq!(
+            task.get_reminder().map(|date| date.to_rfc3339()),
+            Some("2024-05-31T00:00:00+00:00".to_string())
+        );
+
+        assert!(import_todo_list("BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\n").is_err());
+    }
+}
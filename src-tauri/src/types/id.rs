@@ -2,7 +2,7 @@ use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::{
     fmt::Display,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
 };
 
 use super::FileType;
@@ -11,9 +11,20 @@ use super::FileType;
 pub struct Id(String);
 
 impl Id {
+    /// Creates id from a path inside of notes_dir, relative paths are taken as already relative
+    /// to notes_dir
     pub fn new(path: &Path, notes_dir: &Path) -> Result<Self> {
-        let path = match path.strip_prefix(notes_dir) {
+        let relative_notes_dir: PathBuf = notes_dir
+            .components()
+            .filter(|c| !matches!(c, Component::RootDir | Component::Prefix(_)))
+            .collect();
+
+        let path = match path
+            .strip_prefix(notes_dir)
+            .or_else(|_| path.strip_prefix(&relative_notes_dir))
+        {
             Ok(p) => p,
+            Err(_) if path.is_relative() => path,
             Err(_) => return Err(anyhow!("Path is not in notes_dir")),
         };
 
@@ -50,8 +61,7 @@ impl Id {
     }
 
     pub fn get_type(&self) -> Option<FileType> {
-        let extension = self.0.split('.').last().unwrap_or("");
-        FileType::from_str(extension)
+        FileType::from_file_name(self.get_file_name())
     }
 
     pub fn create_id(name: &str, parent_id: &Id, file_type: FileType, notes_dir: &Path) -> Self {
@@ -67,6 +77,60 @@ impl Id {
         Self::new(&PathBuf::from(id), notes_dir).unwrap()
     }
 
+    /// Id of an entry named `name` inside of this directory
+    pub fn join(&self, name: &str) -> Self {
+        match self.0.is_empty() {
+            true => Self(name.to_string()),
+            false => Self(format!("{}/{}", self.0, name)),
+        }
+    }
+
+    pub fn get_file_name(&self) -> &str {
+        self.0.rsplit('/').next().unwrap_or(&self.0)
+    }
+
+    /// Returns true if this id is the same as or nested inside of `parent`
+    pub fn is_under(&self, parent: &Id) -> bool {
+        parent.0.is_empty() || self == parent || self.0.starts_with(&format!("{}/", parent.0))
+    }
+
+    /// Id of the same entry after `from` was moved to `to`
+    pub fn replace_prefix(&self, from: &Id, to: &Id) -> Self {
+        match self == from {
+            true => to.clone(),
+            false => to.join(&self.0[from.0.len() + 1..]),
+        }
+    }
+
+    /// Id of the file renamed to `name`, keeping the parent and the whole extension, so
+    /// `a/b.todo.csv` renamed to `c` becomes `a/c.todo.csv` and `a/b.jpg` becomes `a/c.jpg`
+    pub fn with_name(&self, name: &str) -> Self {
+        let parent = match self.0.rsplit_once('/') {
+            Some((parent, _)) => format!("{}/", parent),
+            None => String::new(),
+        };
+        let extension = match self.get_file_name().split_once('.') {
+            Some((_, extension)) => format!(".{}", extension),
+            None => String::new(),
+        };
+
+        Self(format!("{}{}{}", parent, name, extension))
+    }
+
+    /// Appends suffix to the name, keeping the parent and extension, `a/b.todo.csv` with
+    /// suffix ` (1)` becomes `a/b (1).todo.csv`
+    pub fn with_suffix(&self, suffix: &str) -> Self {
+        let (parent, name) = match self.0.rsplit_once('/') {
+            Some((parent, name)) => (format!("{}/", parent), name),
+            None => (String::new(), self.0.as_str()),
+        };
+        let (stem, extension) = match name.split_once('.') {
+            Some((stem, extension)) => (stem, format!(".{}", extension)),
+            None => (name, String::new()),
+        };
+
+        Self(format!("{}{}{}{}", parent, stem, suffix, extension))
+    }
 }
 
 impl Display for Id {
@@ -123,6 +187,59 @@ mod tests {
         assert_eq!(parent_id, test_parent_id);
     }
 
+    fn id(id: &str) -> Id {
+        Id::from_string(id.to_string())
+    }
+
+    #[test]
+    fn test_join() {
+        assert_eq!(id("").join("a.md"), id("a.md"));
+        assert_eq!(id("a").join("b.md"), id("a/b.md"));
+        assert_eq!(id("a/b").join("c"), id("a/b/c"));
+    }
+
+    #[test]
+    fn test_is_under() {
+        assert!(id("a/b.md").is_under(&id("")));
+        assert!(id("").is_under(&id("")));
+        assert!(id("a/b").is_under(&id("a/b")));
+        assert!(id("a/b/c.md").is_under(&id("a/b")));
+        assert!(id("a/b/c/d.md").is_under(&id("a")));
+        // prefixes only count on a `/` boundary
+        assert!(!id("a/bc").is_under(&id("a/b")));
+        assert!(!id("a/bc/d.md").is_under(&id("a/b")));
+        assert!(!id("a").is_under(&id("a/b")));
+    }
+
+    #[test]
+    fn test_replace_prefix() {
+        assert_eq!(id("a/b").replace_prefix(&id("a/b"), &id("c")), id("c"));
+        assert_eq!(
+            id("a/b/c.md").replace_prefix(&id("a/b"), &id("x/y")),
+            id("x/y/c.md")
+        );
+        // entries moved to the root lose the prefix
+        assert_eq!(
+            id("a/b/c.md").replace_prefix(&id("a"), &id("")),
+            id("b/c.md")
+        );
+    }
+
+    #[test]
+    fn test_with_name() {
+        let image = Id::from_string("pepa/photo.jpg".to_string());
+        assert_eq!(image.with_name("cat").as_str(), "pepa/cat.jpg");
+
+        let svg = Id::from_string("drawing.svg".to_string());
+        assert_eq!(svg.with_name("plan").as_str(), "plan.svg");
+
+        let todo = Id::from_string("a/b/list.todo.csv".to_string());
+        assert_eq!(
+            todo.with_name("groceries").as_str(),
+            "a/b/groceries.todo.csv"
+        );
+    }
+
     #[test]
     fn test_get_type() {
         let notes_dir = PathBuf::from("/home/user/notes");
@@ -153,6 +270,21 @@ mod tests {
         assert_eq!(invalid_type, None);
     }
 
+    #[test]
+    fn test_with_suffix() {
+        let id = Id::from_string("pepa/list.todo.csv".to_string());
+        assert_eq!(id.with_suffix(" (1)").as_str(), "pepa/list (1).todo.csv");
+
+        let id = Id::from_string("folder".to_string());
+        assert_eq!(id.with_suffix(" (2)").as_str(), "folder (2)");
+
+        let id = Id::from_string("a/b.c.d.png".to_string());
+        assert_eq!(id.with_suffix("-2").as_str(), "a/b-2.c.d.png");
+
+        let id = Id::from_string("a.md".to_string());
+        assert_eq!(id.with_suffix(" (1)").as_str(), "a (1).md");
+    }
+
     #[test]
     fn test_iter_id() {
         let notes_dir = PathBuf::from("/home/user/notes");
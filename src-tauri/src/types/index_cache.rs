@@ -0,0 +1,102 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::{self, create_dir_all},
+    path::{Path, PathBuf},
+};
+
+use super::{FilePreview, Id, IndexedLine, IndexedLink, Task};
+
+/// Bump whenever the shape of the cached data changes, old caches are then thrown away
+pub const INDEX_CACHE_VERSION: u32 = 8;
+
+pub const APP_DIR: &str = ".noter";
+const INDEX_CACHE_FILE: &str = "index.json";
+
+/// Size and modification time of a file at the time it was indexed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FileStamp {
+    pub size: u64,
+    pub modified_at: DateTime<Utc>,
+}
+
+impl FileStamp {
+    pub fn from_metadata(metadata: &fs::Metadata) -> Result<Self> {
+        Ok(Self {
+            size: metadata.len(),
+            modified_at: metadata.modified()?.into(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedFile {
+    pub stamp: FileStamp,
+    pub preview: FilePreview,
+    pub lines: Vec<IndexedLine>,
+    pub links: Vec<IndexedLink>,
+    pub tags: Vec<String>,
+    pub tasks: Vec<Task>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexCache {
+    version: u32,
+    files: Vec<CachedFile>,
+}
+
+impl IndexCache {
+    pub fn new(files: Vec<CachedFile>) -> Self {
+        Self {
+            version: INDEX_CACHE_VERSION,
+            files,
+        }
+    }
+
+    pub fn get_cache_file_path(notes_dir: &Path) -> PathBuf {
+        notes_dir.join(APP_DIR).join(INDEX_CACHE_FILE)
+    }
+
+    /// Returns cached files by their id, a missing, unreadable or outdated cache yields nothing
+    /// so the index is rebuilt from scratch
+    pub fn load(notes_dir: &Path) -> HashMap<Id, CachedFile> {
+        let path = Self::get_cache_file_path(notes_dir);
+        let cache: Self = match fs::read(&path).map(|raw| serde_json::from_slice(&raw)) {
+            Ok(Ok(cache)) => cache,
+            Ok(Err(e)) => {
+                println!("Index cache is corrupted, rebuilding; {:?}", e);
+                return HashMap::new();
+            }
+            Err(_) => return HashMap::new(),
+        };
+
+        if cache.version != INDEX_CACHE_VERSION {
+            println!(
+                "Index cache version {} is outdated, rebuilding",
+                cache.version
+            );
+            return HashMap::new();
+        }
+
+        cache
+            .files
+            .into_iter()
+            .map(|file| (file.preview.id.clone(), file))
+            .collect()
+    }
+
+    pub fn store(&self, notes_dir: &Path) -> Result<()> {
+        let path = Self::get_cache_file_path(notes_dir);
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+
+        // write to a temporary file first so a crash never leaves half written cache behind
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_vec(self)?)?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+}
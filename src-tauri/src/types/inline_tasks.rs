@@ -0,0 +1,237 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+
+use super::task_formats::{
+    format_date, parse_checklist_item, parse_date, parse_words, take_priority,
+};
+use super::{Repeat, TagIndex, Task};
+
+const DONE_MARKER: &str = "✅";
+
+/// Checklist item written in a note, like `- [ ] Call vendor 📅 2026-11-02 ⏫`. The task id
+/// of the task is its line.
+#[derive(Debug, Clone)]
+pub struct InlineTask {
+    /// 1-based and relative to the note body, like the lines of links
+    pub line: usize,
+    pub task: Task,
+}
+
+/// Priority markers from the highest to the lowest
+fn get_marker_priority(marker: &str) -> Option<u8> {
+    match marker {
+        "🔺" => Some(9),
+        "⏫" => Some(7),
+        "🔼" => Some(5),
+        "🔽" => Some(3),
+        "⏬" => Some(1),
+        _ => None,
+    }
+}
+
+/// Sets the date the marker stands for, returns false for other words
+fn set_marker_date(task: &mut Task, marker: &str, date: DateTime<Utc>) -> bool {
+    match marker {
+        "📅" | "📆" | "🗓" => task.set_due_date(Some(date)),
+        "🛫" | "⏳" => task.set_start_date(Some(date)),
+        "✅" => task.set_completion(task.is_completed(), Some(date)),
+        "⏰" => task.set_reminder(Some(date)),
+        _ => return false,
+    }
+    true
+}
+
+/// Reads `every day`, `every 2 weeks` and the like
+fn parse_every(task: &mut Task, words: &[&str]) -> usize {
+    let (interval, unit, read) = match words {
+        ["every", unit, ..] if !unit.starts_with(|c: char| c.is_ascii_digit()) => (1, *unit, 2),
+        ["every", interval, unit, ..] => match interval.parse::<u32>() {
+            Ok(interval) => (interval, *unit, 3),
+            Err(_) => return 0,
+        },
+        _ => return 0,
+    };
+    let repeat = match unit.trim_end_matches('s') {
+        "day" => Repeat::Daily,
+        "week" => Repeat::Weekly,
+        "month" => Repeat::Monthly,
+        "year" => Repeat::Yearly,
+        _ => return 0,
+    };
+
+    task.set_repeat(Some(repeat), interval, Vec::new());
+    read
+}
+
+/// Takes the date, priority and repeat markers out of the text and reads the other words like
+/// a todo.txt task, so `due:2026-11-02` and `+project` work as well. The completion date is
+/// only kept for tasks marked as done before.
+fn parse_markers(task: &mut Task, text: &str) {
+    let words: Vec<&str> = text
+        .split_whitespace()
+        .map(|word| word.trim_end_matches('\u{fe0f}'))
+        .collect();
+    let mut rest = Vec::new();
+
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i];
+        i += 1;
+
+        if let Some(priority) = get_marker_priority(word) {
+            task.set_priority(priority);
+            continue;
+        }
+        if word == "🔁" {
+            i += parse_every(task, &words[i..]);
+            continue;
+        }
+        let date = words.get(i).and_then(|date| parse_date(date));
+        match date.is_some_and(|date| set_marker_date(task, word, date)) {
+            true => i += 1,
+            false => rest.push(word),
+        }
+    }
+
+    parse_words(task, &rest.join(" "));
+}
+
+fn parse_inline_task(line: &str, number: usize) -> Option<InlineTask> {
+    let (checked, text) = parse_checklist_item(line.trim_start())?;
+    let mut task = Task::new(number as u32, String::new());
+
+    let (priority, text) = take_priority(text);
+    task.set_priority(priority.unwrap_or(0));
+    task.set_completion(checked, None);
+    parse_markers(&mut task, text);
+    if task.get_content().is_empty() {
+        return None;
+    }
+
+    Some(InlineTask { line: number, task })
+}
+
+/// Lines of the body outside of fenced code blocks with their 1-based numbers
+fn get_unfenced_lines(body: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut in_fence = false;
+    body.lines()
+        .enumerate()
+        .filter(move |(_, line)| {
+            let is_fence = TagIndex::is_code_fence(line);
+            in_fence ^= is_fence;
+            !in_fence && !is_fence
+        })
+        .map(|(i, line)| (i + 1, line))
+}
+
+/// Checklist items of the note body, nested items are read as tasks of their own, items in
+/// fenced code blocks are skipped
+pub fn find_inline_tasks(body: &str) -> Vec<InlineTask> {
+    get_unfenced_lines(body)
+        .filter_map(|(number, line)| parse_inline_task(line, number))
+        .collect()
+}
+
+/// Checks or unchecks the checklist item on the line, checked items get a `✅` completion date
+/// and unchecked ones lose it. Only that line of the body changes.
+pub fn toggle_inline_task(body: &str, line: usize, now: DateTime<Utc>) -> Result<String> {
+    let mut lines: Vec<String> = body.split_inclusive('\n').map(String::from).collect();
+    let raw = lines
+        .get_mut(line.wrapping_sub(1))
+        .ok_or_else(|| anyhow!("Line {} is not in the note", line))?;
+    if !get_unfenced_lines(body).any(|(number, _)| number == line) {
+        return Err(anyhow!("Line {} is not a task", line));
+    }
+
+    let content_length = raw.trim_end_matches(['\r', '\n']).len();
+    let (content, ending) = raw.split_at(content_length);
+    let ending = ending.to_string();
+    let checked = match parse_checklist_item(content.trim_start()) {
+        Some((checked, _)) => checked,
+        None => return Err(anyhow!("Line {} is not a task", line)),
+    };
+
+    // the mark follows the indentation and the three bytes of `- [`
+    let mark = content.len() - content.trim_start().len() + 3;
+    let mut toggled = format!(
+        "{}{}{}",
+        &content[..mark],
+        if checked { ' ' } else { 'x' },
+        &content[mark + 1..]
+    );
+
+    match checked {
+        true => {
+            if let Some(start) = toggled.find(&format!(" {}", DONE_MARKER)) {
+                let after = &toggled[start + 1 + DONE_MARKER.len()..];
+                let date = after.trim_start();
+                let end = match date.get(..10).and_then(parse_date) {
+                    Some(_) => toggled.len() - date.len() + 10,
+                    None => toggled.len() - after.len(),
+                };
+                toggled.replace_range(start..end, "");
+            }
+        }
+        false => toggled.push_str(&format!(" {} {}", DONE_MARKER, format_date(&now))),
+    }
+
+    *raw = toggled + &ending;
+    Ok(lines.concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_inline_tasks() {
+        let body = "# Vendors\n\
+            - [ ] Call vendor 📅 2026-11-02 ⏫ +work\n\
+            Some text\n\
+            \x20 - [x] Send invoice ✅ 2026-10-01 🔁 every 2 weeks\n\
+            - [ ] \n";
+        let tasks = find_inline_tasks(body);
+        assert_eq!(tasks.len(), 2);
+
+        assert_eq!(tasks[0].line, 2);
+        assert_eq!(tasks[0].task.get_task_id(), 2);
+        assert_eq!(tasks[0].task.get_content(), "Call vendor");
+        assert_eq!(tasks[0].task.get_due_date(), parse_date("2026-11-02"));
+        assert_eq!(tasks[0].task.get_priority(), 7);
+        assert_eq!(tasks[0].task.get_project(), "work");
+
+        assert_eq!(tasks[1].line, 4);
+        assert!(tasks[1].task.is_completed());
+        assert_eq!(tasks[1].task.get_completed_at(), parse_date("2026-10-01"));
+        assert_eq!(tasks[1].task.get_repeat(), Some(Repeat::Weekly));
+        assert_eq!(tasks[1].task.get_repeat_interval(), 2);
+    }
+
+    #[test]
+    fn test_fenced_tasks() {
+        let body = "- [ ] Real task\n```md\n- [ ] Example task\n```\n  ~~~\n- [x] Also an example\n  ~~~\n- [ ] Another task";
+        let lines: Vec<usize> = find_inline_tasks(body).iter().map(|t| t.line).collect();
+        assert_eq!(lines, vec![1, 8]);
+
+        let now = parse_date("2026-10-18").unwrap();
+        assert!(toggle_inline_task(body, 3, now).is_err());
+        assert!(toggle_inline_task(body, 8, now).is_ok());
+    }
+
+    #[test]
+    fn test_toggle_inline_task() {
+        let body = "Intro\r\n- [ ] Call vendor 📅 2026-11-02\r\nOutro";
+        let now = parse_date("2026-10-18").unwrap();
+
+        let checked = toggle_inline_task(body, 2, now).unwrap();
+        assert_eq!(
+            checked,
+            "Intro\r\n- [x] Call vendor 📅 2026-11-02 ✅ 2026-10-18\r\nOutro"
+        );
+        assert_eq!(toggle_inline_task(&checked, 2, now).unwrap(), body);
+
+        assert!(toggle_inline_task(body, 1, now).is_err());
+        assert!(toggle_inline_task(body, 0, now).is_err());
+        assert!(toggle_inline_task(body, 4, now).is_err());
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{File, FileContent, FileType, Id, LinkResolver, Note};
+
+/// Raw `[[link]]` found in a note, line is 1-based and context is the whole line it is on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedLink {
+    pub line: usize,
+    pub link: String,
+    pub context: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutgoingLink {
+    pub line: usize,
+    pub link: String,
+    pub target: Id,
+    pub exists: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Backlink {
+    pub source: Id,
+    pub line: usize,
+    pub link: String,
+    pub context: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteLinks {
+    pub id: Id,
+    pub outgoing: Vec<OutgoingLink>,
+    pub backlinks: Vec<Backlink>,
+}
+
+/// Links of every note in the vault together with a reverse index, so backlinks of a note can
+/// be found without reading other notes
+#[derive(Default)]
+pub struct LinkIndex {
+    resolver: LinkResolver,
+    links: HashMap<Id, Vec<IndexedLink>>,
+    /// lowercase file name a link points to -> notes containing such link, links are resolved
+    /// only when asked for, so they follow files being added and removed
+    sources: HashMap<String, HashSet<Id>>,
+}
+
+impl LinkIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_note_links(note: &Note) -> Vec<IndexedLink> {
+        let lines: Vec<&str> = note.get_body().lines().collect();
+
+        note.find_link_spans()
+            .into_iter()
+            .map(|span| IndexedLink {
+                line: span.line,
+                link: note.get_link_text(&span).to_string(),
+                context: lines
+                    .get(span.line - 1)
+                    .map(|line| line.trim().to_string())
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// Key of the file name the link can resolve to, matches the name lookup of `LinkResolver`
+    fn link_key(link: &str) -> String {
+        let target = LinkResolver::link_target(link);
+        let name = target.rsplit('/').next().unwrap_or(target).to_lowercase();
+        match Id::from_string(name.clone()).get_type() {
+            Some(_) => name,
+            None => format!("{}.{}", name, FileType::Note.to_string()),
+        }
+    }
+
+    fn id_key(id: &Id) -> String {
+        id.get_file_name().to_lowercase()
+    }
+
+    pub fn insert_file(&mut self, file: &File) {
+        let links = match &file.content {
+            FileContent::Note(note) => Self::get_note_links(note),
+            _ => Vec::new(),
+        };
+        self.insert_links(&file.id, links);
+    }
+
+    pub fn insert_links(&mut self, id: &Id, links: Vec<IndexedLink>) {
+        self.delete_file(id);
+        self.resolver.insert(id.clone());
+
+        if links.is_empty() {
+            return;
+        }
+        for link in &links {
+            self.sources
+                .entry(Self::link_key(&link.link))
+                .or_default()
+                .insert(id.clone());
+        }
+        self.links.insert(id.clone(), links);
+    }
+
+    pub fn delete_file(&mut self, id: &Id) {
+        self.resolver.remove(id);
+
+        for link in self.links.remove(id).unwrap_or_default() {
+            let key = Self::link_key(&link.link);
+            if let Some(sources) = self.sources.get_mut(&key) {
+                sources.remove(id);
+                if sources.is_empty() {
+                    self.sources.remove(&key);
+                }
+            }
+        }
+    }
+
+    pub fn rename_file(&mut self, id: &Id, new_id: &Id) {
+        if !self.resolver.contains(id) {
+            return;
+        }
+        let links = self.links.get(id).cloned().unwrap_or_default();
+        self.delete_file(id);
+        self.insert_links(new_id, links);
+    }
+
+    pub fn get_links(&self, id: &Id) -> Option<&Vec<IndexedLink>> {
+        self.links.get(id)
+    }
+
+    pub fn get_resolver(&self) -> &LinkResolver {
+        &self.resolver
+    }
+
+    pub fn get_outgoing(&self, id: &Id) -> Vec<OutgoingLink> {
+        self.links
+            .get(id)
+            .into_iter()
+            .flatten()
+            .map(|link| {
+                let target = self.resolver.resolve(&Id::from_string(link.link.clone()));
+                OutgoingLink {
+                    line: link.line,
+                    link: link.link.clone(),
+                    exists: self.resolver.contains(&target),
+                    target,
+                }
+            })
+            .collect()
+    }
+
+    /// Links from other notes that resolve to the note, sorted by the note they are in
+    pub fn get_backlinks(&self, id: &Id) -> Vec<Backlink> {
+        let key = Self::id_key(id);
+        let mut sources: Vec<&Id> = self
+            .sources
+            .get(&key)
+            .into_iter()
+            .flatten()
+            .filter(|source| *source != id)
+            .collect();
+        sources.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        sources
+            .into_iter()
+            .flat_map(|source| {
+                self.links
+                    .get(source)
+                    .into_iter()
+                    .flatten()
+                    .filter(|link| Self::link_key(&link.link) == key)
+                    .filter(|link| {
+                        self.resolver.resolve(&Id::from_string(link.link.clone())) == *id
+                    })
+                    .map(|link| Backlink {
+                        source: source.clone(),
+                        line: link.line,
+                        link: link.link.clone(),
+                        context: link.context.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Pairs of linking note and the resolved link target for every link in the vault
+    pub fn get_edges(&self) -> Vec<(Id, Id)> {
+        self.links
+            .iter()
+            .flat_map(|(source, links)| {
+                links.iter().map(|link| {
+                    let target = self.resolver.resolve(&Id::from_string(link.link.clone()));
+                    (source.clone(), target)
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(id: &str) -> Id {
+        Id::from_string(id.to_string())
+    }
+
+    fn links(body: &str) -> Vec<IndexedLink> {
+        LinkIndex::get_note_links(&Note::new(body.to_string()))
+    }
+
+    #[test]
+    fn test_backlinks() {
+        let mut index = LinkIndex::new();
+        index.insert_links(&id("projects/alpha.md"), links("[[alpha]] links itself"));
+        index.insert_links(
+            &id("inbox.md"),
+            links("# Inbox\n  see [[Alpha|the project]] and [[missing]]"),
+        );
+        index.insert_links(&id("old/archive/alpha.md"), links("[[projects/alpha]]"));
+
+        let backlinks = index.get_backlinks(&id("projects/alpha.md"));
+        assert_eq!(backlinks.len(), 2);
+        assert_eq!(backlinks[0].source, id("inbox.md"));
+        assert_eq!(backlinks[0].line, 2);
+        assert_eq!(
+            backlinks[0].context,
+            "see [[Alpha|the project]] and [[missing]]"
+        );
+
+        // the short link resolves to the shorter path, not to the archived note
+        assert!(index.get_backlinks(&id("old/archive/alpha.md")).is_empty());
+
+        let outgoing = index.get_outgoing(&id("inbox.md"));
+        assert_eq!(outgoing.len(), 2);
+        assert!(outgoing[0].exists);
+        assert_eq!(outgoing[1].target, id("missing.md"));
+        assert!(!outgoing[1].exists);
+    }
+
+    #[test]
+    fn test_rename_and_delete() {
+        let mut index = LinkIndex::new();
+        index.insert_links(&id("inbox.md"), links("[[missing]]"));
+        index.insert_links(&id("missing.md"), Vec::new());
+        assert_eq!(index.get_backlinks(&id("missing.md")).len(), 1);
+
+        index.rename_file(&id("inbox.md"), &id("notes/inbox.md"));
+        assert_eq!(
+            index.get_backlinks(&id("missing.md"))[0].source,
+            id("notes/inbox.md")
+        );
+
+        index.delete_file(&id("notes/inbox.md"));
+        assert!(index.get_backlinks(&id("missing.md")).is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{FileType, Id};
+
+/// Maps raw wiki link targets (as returned by `Note::find_links`) onto ids of files in the vault.
+/// Links may omit the extension and the parent folders, `[[note]]` resolves to `folder/note.md`
+/// when there is no better match.
+#[derive(Clone, Default)]
+pub struct LinkResolver {
+    ids: HashSet<Id>,
+    by_name: HashMap<String, Vec<Id>>,
+}
+
+impl LinkResolver {
+    pub fn new<I: IntoIterator<Item = Id>>(ids: I) -> Self {
+        let mut resolver = Self {
+            ids: HashSet::new(),
+            by_name: HashMap::new(),
+        };
+        ids.into_iter().for_each(|id| resolver.insert(id));
+        resolver
+    }
+
+    pub fn insert(&mut self, id: Id) {
+        let key = Self::file_name(id.as_str()).to_lowercase();
+        let ids = self.by_name.entry(key).or_default();
+        if !ids.contains(&id) {
+            ids.push(id.clone());
+        }
+        self.ids.insert(id);
+    }
+
+    pub fn remove(&mut self, id: &Id) {
+        let key = Self::file_name(id.as_str()).to_lowercase();
+        if let Some(ids) = self.by_name.get_mut(&key) {
+            ids.retain(|i| i != id);
+            if ids.is_empty() {
+                self.by_name.remove(&key);
+            }
+        }
+        self.ids.remove(id);
+    }
+
+    pub fn contains(&self, id: &Id) -> bool {
+        self.ids.contains(id)
+    }
+
+    /// Strips the `|alias` and `#heading` parts of a link, leaving only the target
+    pub fn link_target(link: &str) -> &str {
+        let link = link.split('|').next().unwrap_or("");
+        link.split('#').next().unwrap_or("").trim()
+    }
+
+    /// Returns the id the link points to. Links to files that do not exist resolve to the note
+    /// that would be created for them, so they can still be shown as placeholders.
+    pub fn resolve(&self, link: &Id) -> Id {
+        let target = Self::link_target(link.as_str()).trim_start_matches('/');
+        let has_type = Id::from_string(target.to_string()).get_type().is_some();
+
+        let mut candidates = vec![target.to_string()];
+        if !has_type {
+            candidates.push(format!("{}.{}", target, FileType::Note.to_string()));
+        }
+
+        for candidate in &candidates {
+            let id = Id::from_string(candidate.clone());
+            if self.ids.contains(&id) {
+                return id;
+            }
+        }
+
+        for candidate in &candidates {
+            let name = Self::file_name(candidate).to_lowercase();
+            let suffix = format!("/{}", candidate.to_lowercase());
+            let found = self.by_name.get(&name).and_then(|ids| {
+                ids.iter()
+                    .filter(|id| {
+                        let id = id.as_str().to_lowercase();
+                        id == candidate.to_lowercase() || id.ends_with(&suffix)
+                    })
+                    .min_by_key(|id| (id.as_str().len(), id.as_str().to_string()))
+            });
+            if let Some(id) = found {
+                return id.clone();
+            }
+        }
+
+        Id::from_string(candidates.pop().unwrap_or_default())
+    }
+
+    fn file_name(path: &str) -> &str {
+        path.rsplit('/').next().unwrap_or(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolver() -> LinkResolver {
+        LinkResolver::new(
+            [
+                "inbox.md",
+                "projects/alpha.md",
+                "archive/projects/alpha.md",
+                "img/cat.png",
+            ]
+            .iter()
+            .map(|id| Id::from_string(id.to_string())),
+        )
+    }
+
+    #[test]
+    fn test_resolve_by_name() {
+        let resolver = resolver();
+        let id = resolver.resolve(&Id::from_string("inbox".to_string()));
+        assert_eq!(id.as_str(), "inbox.md");
+
+        let id = resolver.resolve(&Id::from_string("alpha".to_string()));
+        assert_eq!(id.as_str(), "projects/alpha.md");
+
+        let id = resolver.resolve(&Id::from_string("cat.png".to_string()));
+        assert_eq!(id.as_str(), "img/cat.png");
+    }
+
+    #[test]
+    fn test_resolve_alias_and_heading() {
+        let resolver = resolver();
+        let id = resolver.resolve(&Id::from_string(
+            "archive/projects/alpha#Goals|old".to_string(),
+        ));
+        assert_eq!(id.as_str(), "archive/projects/alpha.md");
+    }
+
+    #[test]
+    fn test_resolve_missing() {
+        let mut resolver = resolver();
+        let id = resolver.resolve(&Id::from_string("missing".to_string()));
+        assert_eq!(id.as_str(), "missing.md");
+        assert!(!resolver.contains(&id));
+
+        resolver.remove(&Id::from_string("inbox.md".to_string()));
+        let id = resolver.resolve(&Id::from_string("inbox".to_string()));
+        assert!(!resolver.contains(&id));
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{FileType, Id, LinkResolver, Note};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkChange {
+    pub line: usize,
+    pub old_link: String,
+    pub new_link: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileLinkChanges {
+    /// Id of the note after the rename, it differs from the current one when the note itself
+    /// is inside of the moved directory
+    pub id: Id,
+    pub changes: Vec<LinkChange>,
+    #[serde(skip)]
+    pub old_body: String,
+    #[serde(skip)]
+    pub new_body: String,
+}
+
+/// Links that have to be rewritten when `old_id` is renamed or moved to `new_id`, kept after
+/// applying so the whole operation can be undone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkRewrite {
+    pub old_id: Id,
+    pub new_id: Id,
+    pub files: Vec<FileLinkChanges>,
+}
+
+impl LinkRewrite {
+    /// Finds links pointing to any of the `moved` ids and computes their new text, `resolver`
+    /// has to know the vault as it is before the move
+    pub fn plan(
+        old_id: &Id,
+        new_id: &Id,
+        moved: &[(Id, Id)],
+        notes: Vec<(Id, Note)>,
+        resolver: &LinkResolver,
+    ) -> Self {
+        let moved_ids: HashMap<&Id, &Id> = moved.iter().map(|(from, to)| (from, to)).collect();
+
+        let mut moved_resolver = resolver.clone();
+        for (from, to) in moved {
+            moved_resolver.remove(from);
+            moved_resolver.insert(to.clone());
+        }
+
+        let mut files = Vec::new();
+        for (id, note) in notes {
+            let mut changes = Vec::new();
+            let mut replacements = Vec::new();
+
+            for span in note.find_link_spans() {
+                let link = note.get_link_text(&span);
+                let target = resolver.resolve(&Id::from_string(link.to_string()));
+                let new_target = match moved_ids.get(&target) {
+                    Some(new_target) => *new_target,
+                    None => continue,
+                };
+
+                let new_link = Self::format_link(link, new_target, &moved_resolver);
+                if new_link != link {
+                    changes.push(LinkChange {
+                        line: span.line,
+                        old_link: link.to_string(),
+                        new_link: new_link.clone(),
+                    });
+                    replacements.push((span, new_link));
+                }
+            }
+
+            if !changes.is_empty() {
+                files.push(FileLinkChanges {
+                    id: moved_ids.get(&id).map(|id| (*id).clone()).unwrap_or(id),
+                    changes,
+                    old_body: note.get_body().clone(),
+                    new_body: note.replace_links(&replacements),
+                });
+            }
+        }
+
+        Self {
+            old_id: old_id.clone(),
+            new_id: new_id.clone(),
+            files,
+        }
+    }
+
+    /// Writes a link to `target` in the same style as `old_link`, short links stay short as long
+    /// as they are not ambiguous and `|alias` or `#heading` parts are kept
+    pub fn format_link(old_link: &str, target: &Id, resolver: &LinkResolver) -> String {
+        let suffix = match old_link.find(['#', '|']) {
+            Some(i) => &old_link[i..],
+            None => "",
+        };
+        let old_target = LinkResolver::link_target(old_link);
+        let had_extension = Id::from_string(old_target.to_string()).get_type().is_some();
+
+        let note_extension = format!(".{}", FileType::Note.to_string());
+        let path = match had_extension {
+            false => target
+                .as_str()
+                .strip_suffix(&note_extension)
+                .unwrap_or(target.as_str()),
+            true => target.as_str(),
+        };
+
+        if !old_target.contains('/') {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            if resolver.resolve(&Id::from_string(name.to_string())) == *target {
+                return format!("{}{}", name, suffix);
+            }
+        }
+
+        format!("{}{}", path, suffix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(id: &str) -> Id {
+        Id::from_string(id.to_string())
+    }
+
+    #[test]
+    fn test_format_link() {
+        let resolver = LinkResolver::new([id("b/new.md"), id("a/other.md"), id("c/other.md")]);
+        assert_eq!(
+            LinkRewrite::format_link("old|Alias", &id("b/new.md"), &resolver),
+            "new|Alias"
+        );
+        assert_eq!(
+            LinkRewrite::format_link("a/old.md#Heading", &id("b/new.md"), &resolver),
+            "b/new.md#Heading"
+        );
+        // the short name would resolve to a/other.md
+        assert_eq!(
+            LinkRewrite::format_link("old", &id("c/other.md"), &resolver),
+            "c/other"
+        );
+    }
+
+    #[test]
+    fn test_plan() {
+        let resolver = LinkResolver::new([id("dir/old.md"), id("dir/inner.md"), id("x.md")]);
+        let moved = vec![
+            (id("dir/old.md"), id("moved/old.md")),
+            (id("dir/inner.md"), id("moved/inner.md")),
+        ];
+        let notes = vec![
+            (
+                id("x.md"),
+                Note::new("[[dir/old]] and [[old]]\n[[x]]".to_string()),
+            ),
+            (id("dir/inner.md"), Note::new("[[dir/old|o]]".to_string())),
+        ];
+
+        let rewrite = LinkRewrite::plan(&id("dir"), &id("moved"), &moved, notes, &resolver);
+        assert_eq!(rewrite.files.len(), 2);
+        assert_eq!(
+            rewrite.files[0].new_body,
+            "[[moved/old]] and [[old]]\n[[x]]"
+        );
+        assert_eq!(rewrite.files[0].changes.len(), 1);
+        assert_eq!(rewrite.files[1].id, id("moved/inner.md"));
+        assert_eq!(rewrite.files[1].new_body, "[[moved/old|o]]");
+    }
+}
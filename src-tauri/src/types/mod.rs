@@ -4,8 +4,22 @@ mod file_graph;
 mod file_manager;
 mod file_previews;
 mod files;
+mod ical;
 mod id;
+mod inline_tasks;
+mod index_cache;
+mod link_index;
+mod link_resolver;
+mod link_rewrite;
 mod metadata;
+mod quick_add;
+mod reminders;
+mod search_index;
+mod tag_index;
+mod task_formats;
+mod task_index;
+mod thumbnail_cache;
+mod trash;
 
 pub use config::*;
 pub use data_adapter::*;
@@ -13,5 +27,19 @@ pub use file_graph::*;
 pub use file_manager::*;
 pub use file_previews::*;
 pub use files::*;
+pub use ical::*;
 pub use id::*;
+pub use inline_tasks::*;
+pub use index_cache::*;
+pub use link_index::*;
+pub use link_resolver::*;
+pub use link_rewrite::*;
 pub use metadata::*;
+pub use quick_add::*;
+pub use reminders::*;
+pub use search_index::*;
+pub use tag_index::*;
+pub use task_formats::*;
+pub use task_index::*;
+pub use thumbnail_cache::*;
+pub use trash::*;
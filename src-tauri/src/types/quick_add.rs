@@ -0,0 +1,395 @@
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+
+use super::{Repeat, Task};
+
+/// Date, time and repeat found in the text, they are only combined once every word was read
+#[derive(Default)]
+struct When {
+    date: Option<NaiveDate>,
+    time: Option<NaiveTime>,
+    repeat: Option<(Repeat, u32)>,
+    weekdays: Vec<Weekday>,
+}
+
+/// `!1` to `!5` and `!!!` to `!` from the most important, like other todo apps write them,
+/// mapped onto the 9 to 1 priorities of the todo lists
+fn parse_priority(word: &str) -> Option<u8> {
+    let rest = word.strip_prefix('!')?;
+    let level = match rest.parse::<u8>() {
+        Ok(level) => level,
+        Err(_) if rest.chars().all(|c| c == '!') => 3 - rest.len().min(2) as u8,
+        Err(_) => return None,
+    };
+    match level {
+        0 => None,
+        level => Some(11 - 2 * level.min(5)),
+    }
+}
+
+/// `monday`, `mon` and the like
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    match word.len() >= 3 {
+        true => word.parse().ok(),
+        false => None,
+    }
+}
+
+/// Unit of `days`, `week`, `months` and the like
+fn parse_unit(word: &str) -> Option<Repeat> {
+    match word.trim_end_matches('s') {
+        "day" => Some(Repeat::Daily),
+        "week" => Some(Repeat::Weekly),
+        "month" => Some(Repeat::Monthly),
+        "year" => Some(Repeat::Yearly),
+        _ => None,
+    }
+}
+
+fn parse_count(word: &str) -> Option<u32> {
+    match word {
+        "a" | "an" | "one" => Some(1),
+        "two" => Some(2),
+        "three" => Some(3),
+        word => word.parse().ok(),
+    }
+}
+
+/// `5pm`, `5:30pm`, `17:00`, `noon` and `midnight`. Plain numbers are only times after `at`.
+fn parse_time(word: &str, after_at: bool) -> Option<NaiveTime> {
+    match word {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => {}
+    }
+
+    let (clock, offset) = match (word.strip_suffix("am"), word.strip_suffix("pm")) {
+        (Some(clock), _) => (clock, Some(0)),
+        (_, Some(clock)) => (clock, Some(12)),
+        _ => (word, None),
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour, minute.parse().ok()?),
+        Some(_) => return None,
+        None if offset.is_some() || after_at => (clock, 0),
+        None => return None,
+    };
+    let hour: u32 = hour.parse().ok()?;
+
+    let hour = match offset {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(offset) => hour % 12 + offset,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn add(date: NaiveDate, count: u32, unit: Repeat) -> Option<NaiveDate> {
+    match unit {
+        Repeat::Daily => date.checked_add_days(Days::new(count.into())),
+        Repeat::Weekly => date.checked_add_days(Days::new(7 * u64::from(count))),
+        Repeat::Monthly => date.checked_add_months(Months::new(count)),
+        Repeat::Yearly => date.checked_add_months(Months::new(12 * count)),
+    }
+}
+
+/// The next day that is the weekday, a week from today if today is that day
+fn next_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days = (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    today + Days::new(if days == 0 { 7 } else { days.into() })
+}
+
+/// Reads the date, time or repeat starting at `words[0]`, returns how many words it took
+fn read_when(words: &[String], today: NaiveDate, when: &mut When) -> usize {
+    let word = |i: usize| words.get(i).map(|word| word.as_str());
+
+    match (word(0), word(1), word(2)) {
+        (Some("today" | "tonight"), ..) => {
+            when.date = Some(today);
+            if word(0) == Some("tonight") && when.time.is_none() {
+                when.time = NaiveTime::from_hms_opt(20, 0, 0);
+            }
+            1
+        }
+        (Some("tomorrow" | "tmr"), ..) => {
+            when.date = today.succ_opt();
+            1
+        }
+        (Some("next"), Some(unit), _) if parse_unit(unit).is_some() => {
+            when.date = parse_unit(unit).and_then(|unit| add(today, 1, unit));
+            2
+        }
+        (Some("next" | "on"), Some(day), _) if parse_weekday(day).is_some() => {
+            when.date = parse_weekday(day).map(|day| next_weekday(today, day));
+            2
+        }
+        (Some("in"), Some(count), Some(unit)) if parse_unit(unit).is_some() => {
+            match parse_count(count) {
+                Some(count) => {
+                    when.date = parse_unit(unit).and_then(|unit| add(today, count, unit));
+                    3
+                }
+                None => 0,
+            }
+        }
+        (Some("every"), Some(count), Some(unit))
+            if parse_count(count).is_some() && parse_unit(unit).is_some() =>
+        {
+            when.repeat = parse_unit(unit).zip(parse_count(count));
+            3
+        }
+        (Some("every"), Some(unit), _) if parse_unit(unit).is_some() => {
+            when.repeat = parse_unit(unit).map(|unit| (unit, 1));
+            2
+        }
+        (Some("every"), Some(day), _) if parse_weekday(day).is_some() => {
+            when.repeat = Some((Repeat::Weekly, 1));
+            when.weekdays.extend(parse_weekday(day));
+            2
+        }
+        (Some("daily"), ..) => {
+            when.repeat = Some((Repeat::Daily, 1));
+            1
+        }
+        (Some("weekly"), ..) => {
+            when.repeat = Some((Repeat::Weekly, 1));
+            1
+        }
+        (Some("monthly"), ..) => {
+            when.repeat = Some((Repeat::Monthly, 1));
+            1
+        }
+        (Some("yearly" | "annually"), ..) => {
+            when.repeat = Some((Repeat::Yearly, 1));
+            1
+        }
+        (Some("at"), Some(time), _) if parse_time(time, true).is_some() => {
+            when.time = parse_time(time, true);
+            2
+        }
+        (Some(time), Some(meridiem @ ("am" | "pm")), _)
+            if parse_time(&format!("{}{}", time, meridiem), false).is_some() =>
+        {
+            when.time = parse_time(&format!("{}{}", time, meridiem), false);
+            2
+        }
+        (Some(time), ..) if parse_time(time, false).is_some() => {
+            when.time = parse_time(time, false);
+            1
+        }
+        // abbreviations like `sun` or `sat` are words of their own, they need a `next` or `on`
+        (Some(day), ..) if day.len() > 3 && parse_weekday(day).is_some() => {
+            when.date = parse_weekday(day).map(|day| next_weekday(today, day));
+            1
+        }
+        (Some(date), ..) if NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok() => {
+            when.date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+            1
+        }
+        _ => 0,
+    }
+}
+
+/// Reads a task from a line like `Pay invoice tomorrow 5pm !2 +finance every month`.
+///
+/// Dates are `today`, `tomorrow`, weekday names, `next week`, `in 3 days` or `2026-11-02`,
+/// times are `5pm`, `17:30` or `at 9`. A time without a date is the next time it is that
+/// late, and tasks with a time get a reminder at that time. `!1` or `!!!` is the highest
+/// priority, `+project` the project, `#tag` a tag and `every month`, `every 2 weeks`,
+/// `every friday` or `daily` the repeat. Other words are the content of the task. Dates are
+/// days in the time zone of `now`.
+pub fn parse_quick_add<Tz: TimeZone>(text: &str, now: &DateTime<Tz>) -> Task {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let lower: Vec<String> = words.iter().map(|word| word.to_lowercase()).collect();
+    let today = now.date_naive();
+
+    let mut task = Task::new(0, String::new());
+    let mut when = When::default();
+    let mut content = Vec::new();
+    let mut tags = Vec::new();
+
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i];
+
+        if let Some(priority) = parse_priority(word) {
+            task.set_priority(priority);
+        } else if let Some(project) = word.strip_prefix('+').filter(|p| !p.is_empty()) {
+            task.set_project(project.to_string());
+        } else if let Some(tag) = word.strip_prefix('#').filter(|tag| !tag.is_empty()) {
+            tags.push(tag.to_string());
+        } else {
+            match read_when(&lower[i..], today, &mut when) {
+                0 => content.push(word),
+                read => {
+                    i += read;
+                    continue;
+                }
+            }
+        }
+        i += 1;
+    }
+
+    task.set_content(content.join(" "));
+    task.set_tags(tags);
+    if let Some((repeat, interval)) = when.repeat {
+        task.set_repeat(Some(repeat), interval, when.weekdays.clone());
+    }
+
+    // a repeat on weekdays starts on the first of them
+    let date = when.date.or_else(|| {
+        when.weekdays
+            .iter()
+            .map(|weekday| next_weekday(today, *weekday))
+            .min()
+    });
+    let date = match (date, when.time) {
+        (Some(date), _) => Some(date),
+        (None, Some(time)) if time > now.time() => Some(today),
+        (None, Some(_)) => today.succ_opt(),
+        (None, None) => None,
+    };
+
+    let tz = now.timezone();
+    let to_utc = |date: NaiveDate, time: NaiveTime| -> Option<DateTime<Utc>> {
+        tz.from_local_datetime(&date.and_time(time))
+            .earliest()
+            .map(|date| date.with_timezone(&Utc))
+    };
+    // dates without a time are stored as UTC midnight like in the todo lists
+    let due_date = match (date, when.time) {
+        (Some(date), Some(time)) => to_utc(date, time),
+        (Some(date), None) => Some(date.and_time(NaiveTime::MIN).and_utc()),
+        (None, _) => None,
+    };
+    task.set_due_date(due_date);
+    if when.time.is_some() {
+        task.set_reminder(due_date);
+    }
+    task
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{export_todo_lists, export_todo_txt, Id, TodoList};
+    use chrono::FixedOffset;
+
+    fn at(date: &str, time: &str) -> Option<DateTime<Utc>> {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        let time = NaiveTime::parse_from_str(time, "%H:%M").unwrap();
+        Some(date.and_time(time).and_utc())
+    }
+
+    #[test]
+    fn test_dates() {
+        // a sunday afternoon
+        let now = at("2026-10-18", "14:00").unwrap();
+        let cases = [
+            ("Call mom today", "Call mom", at("2026-10-18", "00:00")),
+            ("Call mom tomorrow", "Call mom", at("2026-10-19", "00:00")),
+            (
+                "Pay rent tomorrow 5pm",
+                "Pay rent",
+                at("2026-10-19", "17:00"),
+            ),
+            ("Standup at 9", "Standup", at("2026-10-19", "09:00")),
+            ("Standup 15:30", "Standup", at("2026-10-18", "15:30")),
+            ("Dinner tonight", "Dinner", at("2026-10-18", "20:00")),
+            ("Review friday", "Review", at("2026-10-23", "00:00")),
+            ("Review on Sunday", "Review", at("2026-10-25", "00:00")),
+            ("Gym next Mon 7 am", "Gym", at("2026-10-19", "07:00")),
+            ("Renew in 3 days", "Renew", at("2026-10-21", "00:00")),
+            ("Renew in a week", "Renew", at("2026-10-25", "00:00")),
+            ("Taxes next month", "Taxes", at("2026-11-18", "00:00")),
+            ("Vote 2026-11-03 noon", "Vote", at("2026-11-03", "12:00")),
+            ("Buy 2 apples", "Buy 2 apples", None),
+            ("Read in the park", "Read in the park", None),
+        ];
+
+        for (text, content, due_date) in cases {
+            let task = parse_quick_add(text, &now);
+            assert_eq!(task.get_content(), content, "{}", text);
+            assert_eq!(task.get_due_date(), due_date, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_time_zone() {
+        // late evening in New York is already the next day in UTC
+        let tz = FixedOffset::west_opt(4 * 3600).unwrap();
+        let now = tz.with_ymd_and_hms(2026, 10, 18, 22, 0, 0).unwrap();
+
+        let task = parse_quick_add("Call mom tomorrow", &now);
+        assert_eq!(task.get_due_date(), at("2026-10-19", "00:00"));
+        let task = parse_quick_add("Call mom tomorrow 9am", &now);
+        assert_eq!(task.get_due_date(), at("2026-10-19", "13:00"));
+    }
+
+    #[test]
+    fn test_priorities() {
+        let now = at("2026-10-18", "14:00").unwrap();
+        let cases = [
+            ("Fix the roof !1", 9),
+            ("Fix the roof !!!", 9),
+            ("Fix the roof !!!!", 9),
+            ("Fix the roof !2", 7),
+            ("Fix the roof !!", 7),
+            ("Fix the roof !3", 5),
+            ("Fix the roof !", 5),
+            ("Fix the roof !5", 1),
+            ("Fix the roof", 0),
+        ];
+        for (text, priority) in cases {
+            let task = parse_quick_add(text, &now);
+            assert_eq!(task.get_content(), "Fix the roof", "{}", text);
+            assert_eq!(task.get_priority(), priority, "{}", text);
+        }
+
+        // the most important quick-add priority is the most important one in every format
+        let todo_list = TodoList::new(vec![parse_quick_add("Fix the roof !!!", &now)]);
+        assert!(export_todo_txt(&todo_list).starts_with("(A) "));
+        let source = Id::from_string("home.todo.csv".to_string());
+        let ics = export_todo_lists(&[(source, todo_list)]);
+        assert!(ics.contains("PRIORITY:1\r\n"));
+    }
+
+    #[test]
+    fn test_fields() {
+        let now = at("2026-10-18", "14:00").unwrap();
+        let task = parse_quick_add("Pay invoice tomorrow 5pm !2 +finance every month", &now);
+        assert_eq!(task.get_content(), "Pay invoice");
+        assert_eq!(task.get_due_date(), at("2026-10-19", "17:00"));
+        assert_eq!(task.get_reminder(), task.get_due_date());
+        assert_eq!(task.get_priority(), 7);
+        assert_eq!(task.get_project(), "finance");
+        assert_eq!(task.get_repeat(), Some(Repeat::Monthly));
+
+        let cases = [
+            ("Water plants every 2 days", Repeat::Daily, 2, vec![]),
+            (
+                "Team sync every Tuesday",
+                Repeat::Weekly,
+                1,
+                vec![Weekday::Tue],
+            ),
+            ("Backup weekly", Repeat::Weekly, 1, vec![]),
+            ("Birthday yearly", Repeat::Yearly, 1, vec![]),
+        ];
+        for (text, repeat, interval, weekdays) in cases {
+            let task = parse_quick_add(text, &now);
+            assert_eq!(task.get_repeat(), Some(repeat), "{}", text);
+            assert_eq!(task.get_repeat_interval(), interval, "{}", text);
+            assert_eq!(task.get_repeat_weekdays(), &weekdays, "{}", text);
+        }
+
+        let task = parse_quick_add("Team sync every tuesday 10am !!! #meeting", &now);
+        assert_eq!(task.get_due_date(), at("2026-10-20", "10:00"));
+        assert_eq!(task.get_priority(), 9);
+        assert_eq!(task.get_tags(), vec!["meeting"]);
+        assert_eq!(task.get_reminder(), task.get_due_date());
+
+        let task = parse_quick_add("Just a note", &now);
+        assert_eq!(task.get_due_date(), None);
+        assert_eq!(task.get_reminder(), None);
+    }
+}
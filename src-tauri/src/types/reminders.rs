@@ -0,0 +1,113 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, create_dir_all},
+    path::{Path, PathBuf},
+};
+
+use super::{Id, APP_DIR};
+
+const REMINDERS_FILE: &str = "reminders.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reminder {
+    pub source: Id,
+    pub task_id: u32,
+    pub content: String,
+    pub at: DateTime<Utc>,
+}
+
+/// How far the reminders were delivered. It is stored in the notes dir, so reminders that came
+/// due while the app was closed are delivered on the next launch.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReminderSchedule {
+    checked_until: DateTime<Utc>,
+}
+
+impl ReminderSchedule {
+    pub fn new(checked_until: DateTime<Utc>) -> Self {
+        Self { checked_until }
+    }
+
+    pub fn get_file_path(notes_dir: &Path) -> PathBuf {
+        notes_dir.join(APP_DIR).join(REMINDERS_FILE)
+    }
+
+    /// Without a stored schedule only reminders from now on are delivered, so the first launch
+    /// does not bring up every reminder of the past
+    pub fn load(notes_dir: &Path) -> Self {
+        fs::read(Self::get_file_path(notes_dir))
+            .ok()
+            .and_then(|raw| serde_json::from_slice(&raw).ok())
+            .unwrap_or_else(|| Self::new(Utc::now()))
+    }
+
+    pub fn store(&self, notes_dir: &Path) -> Result<()> {
+        let path = Self::get_file_path(notes_dir);
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Reminders that came due since the last check, oldest first
+    pub fn take_due(&mut self, reminders: &[Reminder], now: DateTime<Utc>) -> Vec<Reminder> {
+        let mut due: Vec<Reminder> = reminders
+            .iter()
+            .filter(|reminder| reminder.at > self.checked_until && reminder.at <= now)
+            .cloned()
+            .collect();
+        due.sort_by_key(|reminder| reminder.at);
+
+        self.checked_until = self.checked_until.max(now);
+        due
+    }
+
+    pub fn get_next(reminders: &[Reminder], now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        reminders
+            .iter()
+            .map(|reminder| reminder.at)
+            .filter(|at| *at > now)
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_take_due() {
+        let start = Utc::now();
+        let reminder = |task_id: u32, minutes: i64| Reminder {
+            source: Id::from_string("a.todo.csv".to_string()),
+            task_id,
+            content: String::new(),
+            at: start + Duration::minutes(minutes),
+        };
+        let reminders = vec![
+            reminder(1, -5),
+            reminder(2, 10),
+            reminder(3, 5),
+            reminder(4, 60),
+        ];
+
+        let mut schedule = ReminderSchedule::new(start);
+        assert!(schedule.take_due(&reminders, start).is_empty());
+        assert_eq!(
+            ReminderSchedule::get_next(&reminders, start),
+            Some(start + Duration::minutes(5))
+        );
+
+        // the app was closed for half an hour
+        let due = schedule.take_due(&reminders, start + Duration::minutes(30));
+        let ids: Vec<u32> = due.iter().map(|reminder| reminder.task_id).collect();
+        assert_eq!(ids, vec![3, 2]);
+        assert!(schedule
+            .take_due(&reminders, start + Duration::minutes(31))
+            .is_empty());
+    }
+}
@@ -0,0 +1,366 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{File, FileContent, FilePreview, Id};
+
+const SNIPPET_LENGTH: usize = 160;
+const MAX_MATCHES_PER_FILE: usize = 5;
+
+/// Part of a file that is searchable, line is 1-based line number in the file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedLine {
+    pub line: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchMatch {
+    pub line: usize,
+    pub snippet: String,
+    /// (start, end) char offsets of the matched terms inside the snippet
+    pub highlights: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub file: FilePreview,
+    pub score: f64,
+    pub matches: Vec<SearchMatch>,
+}
+
+/// Inverted index over the text content of notes and todo lists
+#[derive(Default)]
+pub struct SearchIndex {
+    /// term -> file -> indexes into the file's lines the term occurs in
+    terms: BTreeMap<String, HashMap<Id, Vec<usize>>>,
+    documents: HashMap<Id, Vec<IndexedLine>>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn tokenize(text: &str) -> Vec<(usize, usize, String)> {
+        let mut tokens = Vec::new();
+        let mut start = None;
+
+        for (i, (byte, c)) in text.char_indices().enumerate() {
+            match (c.is_alphanumeric(), start) {
+                (true, None) => start = Some((i, byte)),
+                (false, Some((char_start, byte_start))) => {
+                    tokens.push((char_start, i, text[byte_start..byte].to_lowercase()));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some((char_start, byte_start)) = start {
+            tokens.push((
+                char_start,
+                text.chars().count(),
+                text[byte_start..].to_lowercase(),
+            ));
+        }
+
+        tokens
+    }
+
+    pub fn get_lines(file: &File) -> Vec<IndexedLine> {
+        match &file.content {
+            FileContent::Note(note) => note
+                .get_body()
+                .lines()
+                .enumerate()
+                .filter(|(_, text)| !text.trim().is_empty())
+                .map(|(i, text)| IndexedLine {
+                    line: i + 1,
+                    text: text.to_string(),
+                })
+                .collect(),
+            FileContent::TodoList(todo_list) => todo_list
+                .get_tasks()
+                .iter()
+                .zip(todo_list.get_task_lines())
+                .flat_map(|(task, line)| {
+                    [task.get_content(), task.get_description()]
+                        .into_iter()
+                        .filter(|text| !text.trim().is_empty())
+                        .map(move |text| IndexedLine {
+                            line,
+                            text: text.to_string(),
+                        })
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn insert_file(&mut self, file: &File) {
+        self.insert_lines(&file.id, Self::get_lines(file));
+    }
+
+    pub fn insert_lines(&mut self, id: &Id, lines: Vec<IndexedLine>) {
+        self.delete_file(id);
+
+        for (index, line) in lines.iter().enumerate() {
+            for (_, _, term) in Self::tokenize(&line.text) {
+                let postings = self
+                    .terms
+                    .entry(term)
+                    .or_default()
+                    .entry(id.clone())
+                    .or_default();
+                if postings.last() != Some(&index) {
+                    postings.push(index);
+                }
+            }
+        }
+
+        self.documents.insert(id.clone(), lines);
+    }
+
+    pub fn delete_file(&mut self, id: &Id) {
+        let lines = match self.documents.remove(id) {
+            Some(lines) => lines,
+            None => return,
+        };
+
+        let terms: HashSet<String> = lines
+            .iter()
+            .flat_map(|line| Self::tokenize(&line.text))
+            .map(|(_, _, term)| term)
+            .collect();
+
+        for term in terms {
+            if let Some(files) = self.terms.get_mut(&term) {
+                files.remove(id);
+                if files.is_empty() {
+                    self.terms.remove(&term);
+                }
+            }
+        }
+    }
+
+    pub fn get_document(&self, id: &Id) -> Option<&Vec<IndexedLine>> {
+        self.documents.get(id)
+    }
+
+    pub fn rename_file(&mut self, id: &Id, new_id: &Id) {
+        if let Some(lines) = self.documents.get(id).cloned() {
+            self.delete_file(id);
+            self.insert_lines(new_id, lines);
+        }
+    }
+
+    /// Files with postings for the query term, the last query term is matched as a prefix so
+    /// results show up while typing
+    fn find_term(&self, term: &str, prefix: bool) -> HashMap<&Id, Vec<(usize, &str)>> {
+        let mut found: HashMap<&Id, Vec<(usize, &str)>> = HashMap::new();
+
+        let terms: Vec<(&String, &HashMap<Id, Vec<usize>>)> = match prefix {
+            true => self
+                .terms
+                .range(term.to_string()..)
+                .take_while(|(t, _)| t.starts_with(term))
+                .collect(),
+            false => self.terms.get_key_value(term).into_iter().collect(),
+        };
+
+        for (term, files) in terms {
+            for (id, lines) in files {
+                let entry = found.entry(id).or_default();
+                lines
+                    .iter()
+                    .for_each(|line| entry.push((*line, term.as_str())));
+            }
+        }
+
+        found
+    }
+
+    fn snippet(text: &str, terms: &HashSet<&str>) -> SearchMatch {
+        let tokens: Vec<(usize, usize)> = Self::tokenize(text)
+            .into_iter()
+            .filter(|(_, _, token)| terms.contains(token.as_str()))
+            .map(|(start, end, _)| (start, end))
+            .collect();
+
+        let length = text.chars().count();
+        let first = tokens.first().map(|(start, _)| *start).unwrap_or(0);
+        let offset = match length > SNIPPET_LENGTH {
+            true => first
+                .saturating_sub(SNIPPET_LENGTH / 4)
+                .min(length - SNIPPET_LENGTH),
+            false => 0,
+        };
+
+        let snippet: String = text.chars().skip(offset).take(SNIPPET_LENGTH).collect();
+        let highlights = tokens
+            .into_iter()
+            .filter(|(start, end)| *start >= offset && *end <= offset + SNIPPET_LENGTH)
+            .map(|(start, end)| (start - offset, end - offset))
+            .collect();
+
+        SearchMatch {
+            line: 0,
+            snippet,
+            highlights,
+        }
+    }
+
+    /// Returns files containing all query terms ranked by tf-idf, together with the lines
+    /// where the terms were found
+    pub fn search(&self, query: &str) -> Vec<(Id, f64, Vec<SearchMatch>)> {
+        let query: Vec<String> = Self::tokenize(query)
+            .into_iter()
+            .map(|(_, _, term)| term)
+            .collect();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let documents = self.documents.len().max(1) as f64;
+        let mut scores: HashMap<&Id, f64> = HashMap::new();
+        let mut hits: HashMap<&Id, Vec<(usize, &str)>> = HashMap::new();
+
+        for (i, term) in query.iter().enumerate() {
+            let found = self.find_term(term, i == query.len() - 1);
+            let idf = (1.0 + documents / (found.len().max(1) as f64)).ln();
+
+            scores.retain(|id, _| found.contains_key(id));
+            if i > 0 && scores.is_empty() {
+                return Vec::new();
+            }
+
+            for (id, lines) in found {
+                if i > 0 && !scores.contains_key(id) {
+                    continue;
+                }
+                let tf = (1.0 + lines.len() as f64).ln();
+                *scores.entry(id).or_default() += tf * idf;
+                hits.entry(id).or_default().extend(lines);
+            }
+        }
+
+        let mut results: Vec<(Id, f64, Vec<SearchMatch>)> = scores
+            .into_iter()
+            .map(|(id, score)| {
+                let mut lines = hits.remove(id).unwrap_or_default();
+                lines.sort();
+
+                let terms: HashSet<&str> = lines.iter().map(|(_, term)| *term).collect();
+                let mut line_indexes: Vec<usize> = lines.iter().map(|(line, _)| *line).collect();
+                line_indexes.dedup();
+
+                let matches = line_indexes
+                    .into_iter()
+                    .take(MAX_MATCHES_PER_FILE)
+                    .filter_map(|index| self.documents.get(id)?.get(index))
+                    .map(|line| SearchMatch {
+                        line: line.line,
+                        ..Self::snippet(&line.text, &terms)
+                    })
+                    .collect();
+
+                (id.clone(), score, matches)
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.as_str().cmp(b.0.as_str()))
+        });
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> SearchIndex {
+        let mut index = SearchIndex::new();
+        let lines = |text: &str| {
+            text.lines()
+                .enumerate()
+                .map(|(i, text)| IndexedLine {
+                    line: i + 1,
+                    text: text.to_string(),
+                })
+                .collect()
+        };
+        index.insert_lines(
+            &Id::from_string("rust.md".to_string()),
+            lines("# Rust\nOwnership and borrowing\nRust rust rust"),
+        );
+        index.insert_lines(
+            &Id::from_string("garden.md".to_string()),
+            lines("Tomatoes need sun\nBorrowing a shovel from Jiří"),
+        );
+        index
+    }
+
+    #[test]
+    fn test_search_ranking() {
+        let index = index();
+        let results = index.search("borrowing");
+        assert_eq!(results.len(), 2);
+
+        let results = index.search("rust borrowing");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0.as_str(), "rust.md");
+        assert_eq!(results[0].2[0].line, 1);
+    }
+
+    #[test]
+    fn test_search_prefix_and_highlights() {
+        let index = index();
+        let results = index.search("shov");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].2[0].line, 2);
+        assert_eq!(results[0].2[0].highlights, vec![(12, 18)]);
+
+        let results = index.search("jiří");
+        assert_eq!(results.len(), 1);
+    }
+
+    #[test]
+    fn test_search_todo_list() {
+        use crate::types::{FileSerializable, TodoList};
+        use chrono::Utc;
+
+        let csv = "task_id,content,description\n\
+            1,Buy milk,\"Two liters,\nthe cheap one\"\n\
+            2,Call the plumber,\n";
+        let todo_list = TodoList::custom_deserialize(csv.as_bytes()).unwrap();
+        let file = File::new(
+            Id::from_string("home.todo.csv".to_string()),
+            FileContent::TodoList(todo_list),
+            Utc::now(),
+            Utc::now(),
+        );
+        let mut index = SearchIndex::new();
+        index.insert_file(&file);
+
+        let lines: Vec<&str> = csv.lines().collect();
+        for (query, content) in [("milk", "Buy milk"), ("plumber", "Call the plumber")] {
+            let line = index.search(query)[0].2[0].line;
+            assert!(lines[line - 1].contains(content), "{}", query);
+        }
+        assert_eq!(index.search("cheap")[0].2[0].line, 2);
+    }
+
+    #[test]
+    fn test_delete_and_rename() {
+        let mut index = index();
+        index.delete_file(&Id::from_string("garden.md".to_string()));
+        assert_eq!(index.search("tomatoes").len(), 0);
+
+        let new_id = Id::from_string("lang/rust.md".to_string());
+        index.rename_file(&Id::from_string("rust.md".to_string()), &new_id);
+        assert_eq!(index.search("ownership")[0].0, new_id);
+    }
+}
@@ -0,0 +1,385 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+
+use super::{File, FileContent, Id, Note};
+
+const FRONTMATTER_TAGS: &str = "tags";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// Tags of every file in the vault, tags are case-insensitive and are kept lowercased. Nested
+/// tags like `project/alpha` are separate tags, but they are found when browsing `project`.
+#[derive(Default)]
+pub struct TagIndex {
+    files: HashMap<Id, Vec<String>>,
+    tags: BTreeMap<String, HashSet<Id>>,
+}
+
+impl TagIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn is_tag_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '_' || c == '-' || c == '/'
+    }
+
+    /// Strips the leading `#` and validates the tag, the case is kept
+    pub fn clean(tag: &str) -> Option<&str> {
+        let tag = tag.trim().trim_start_matches('#').trim_matches('/');
+        let valid = !tag.is_empty()
+            && tag.chars().all(Self::is_tag_char)
+            && tag.chars().any(|c| !c.is_ascii_digit());
+        valid.then_some(tag)
+    }
+
+    pub fn normalize(tag: &str) -> Option<String> {
+        Self::clean(tag).map(|tag| tag.to_lowercase())
+    }
+
+    /// Returns true for the lines opening and closing fenced code blocks
+    pub fn is_code_fence(line: &str) -> bool {
+        let trimmed = line.trim_start();
+        trimmed.starts_with("```") || trimmed.starts_with("~~~")
+    }
+
+    /// Byte ranges of inline `#tags` in the text without the `#`, tags inside of code are
+    /// skipped, as are `#` not preceded by whitespace (links to headings, urls)
+    pub fn find_inline_tags(text: &str) -> Vec<(usize, usize)> {
+        let mut spans = Vec::new();
+        let mut offset = 0;
+        let mut in_fence = false;
+
+        for line in text.split_inclusive('\n') {
+            let line_offset = offset;
+            offset += line.len();
+
+            if Self::is_code_fence(line) {
+                in_fence = !in_fence;
+                continue;
+            }
+            if in_fence {
+                continue;
+            }
+
+            let mut in_code = false;
+            let mut previous: Option<char> = None;
+            for (i, c) in line.char_indices() {
+                match c {
+                    '`' => in_code = !in_code,
+                    '#' if !in_code && previous.is_none_or(char::is_whitespace) => {
+                        let start = i + 1;
+                        let end = line[start..]
+                            .find(|c: char| !Self::is_tag_char(c))
+                            .map_or(line.len(), |end| start + end);
+                        let tag = line[start..end].trim_end_matches('/');
+
+                        if !tag.starts_with('/') && Self::clean(tag).is_some() {
+                            spans.push((line_offset + start, line_offset + start + tag.len()));
+                        }
+                    }
+                    _ => {}
+                }
+                previous = Some(c);
+            }
+        }
+
+        spans
+    }
+
+    /// Tags of a `tags:` frontmatter property, either a list or a comma or space separated string
+    fn frontmatter_tags(value: &Value) -> Vec<String> {
+        match value {
+            Value::Sequence(tags) => tags
+                .iter()
+                .filter_map(|tag| tag.as_str().map(|tag| tag.to_string()))
+                .collect(),
+            Value::String(tags) => tags
+                .split([',', ' '])
+                .filter(|tag| !tag.is_empty())
+                .map(|tag| tag.to_string())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn get_note_tags(note: &Note) -> Vec<String> {
+        let body = note.get_body();
+        let mut tags: Vec<&str> = Self::find_inline_tags(body)
+            .into_iter()
+            .map(|(start, end)| &body[start..end])
+            .collect();
+
+        let frontmatter = note
+            .get_frontmatter()
+            .and_then(|frontmatter| frontmatter.get(FRONTMATTER_TAGS))
+            .map(Self::frontmatter_tags)
+            .unwrap_or_default();
+        tags.extend(frontmatter.iter().map(|tag| tag.as_str()));
+
+        tags.into_iter().filter_map(Self::normalize).collect()
+    }
+
+    pub fn get_file_tags(file: &File) -> Vec<String> {
+        let mut tags: Vec<String> = match &file.content {
+            FileContent::Note(note) => Self::get_note_tags(note),
+            FileContent::TodoList(todo_list) => todo_list
+                .get_tasks()
+                .iter()
+                .flat_map(|task| task.get_tags())
+                .filter_map(|tag| Self::normalize(&tag))
+                .collect(),
+            _ => Vec::new(),
+        };
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    pub fn insert_file(&mut self, file: &File) {
+        self.insert_tags(&file.id, Self::get_file_tags(file));
+    }
+
+    pub fn insert_tags(&mut self, id: &Id, tags: Vec<String>) {
+        self.delete_file(id);
+        if tags.is_empty() {
+            return;
+        }
+
+        for tag in &tags {
+            self.tags.entry(tag.clone()).or_default().insert(id.clone());
+        }
+        self.files.insert(id.clone(), tags);
+    }
+
+    pub fn delete_file(&mut self, id: &Id) {
+        for tag in self.files.remove(id).unwrap_or_default() {
+            if let Some(files) = self.tags.get_mut(&tag) {
+                files.remove(id);
+                if files.is_empty() {
+                    self.tags.remove(&tag);
+                }
+            }
+        }
+    }
+
+    pub fn rename_file(&mut self, id: &Id, new_id: &Id) {
+        if let Some(tags) = self.files.get(id).cloned() {
+            self.insert_tags(new_id, tags);
+            self.delete_file(id);
+        }
+    }
+
+    pub fn get_tags(&self, id: &Id) -> Option<&Vec<String>> {
+        self.files.get(id)
+    }
+
+    /// All tags with the number of files they are used in
+    pub fn list(&self) -> Vec<TagCount> {
+        self.tags
+            .iter()
+            .map(|(tag, files)| TagCount {
+                tag: tag.clone(),
+                count: files.len(),
+            })
+            .collect()
+    }
+
+    /// Files tagged with the tag or any tag nested in it, with `prefix` every tag starting with
+    /// the text matches
+    pub fn get_files(&self, tag: &str, prefix: bool) -> Vec<Id> {
+        let tag = match Self::normalize(tag) {
+            Some(tag) => tag,
+            None => return Vec::new(),
+        };
+        let nested = format!("{}/", tag);
+
+        let mut ids: Vec<Id> = self
+            .tags
+            .range(tag.clone()..)
+            .take_while(|(t, _)| t.starts_with(&tag))
+            .filter(|(t, _)| prefix || **t == tag || t.starts_with(&nested))
+            .flat_map(|(_, files)| files.iter().cloned())
+            .collect::<HashSet<Id>>()
+            .into_iter()
+            .collect();
+        ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        ids
+    }
+
+    /// New name of `tag` after `old` was renamed to `new`, nested tags are moved along
+    pub fn rename_tag(tag: &str, old: &str, new: &str) -> Option<String> {
+        let lowercase = tag.to_lowercase();
+        if lowercase == old {
+            return Some(new.to_string());
+        }
+
+        lowercase.strip_prefix(old)?.strip_prefix('/')?;
+        let rest: String = tag.chars().skip(old.chars().count()).collect();
+        Some(format!("{}{}", new, rest))
+    }
+
+    fn rename_all(tags: Vec<String>, old: &str, new: &str) -> (Vec<String>, bool) {
+        let mut changed = false;
+        let mut renamed: Vec<String> = Vec::new();
+
+        for tag in tags {
+            let tag = match Self::rename_tag(tag.trim_start_matches('#'), old, new) {
+                Some(tag) => {
+                    changed = true;
+                    tag
+                }
+                None => tag,
+            };
+            // merging into an existing tag must not leave duplicates behind
+            if !renamed
+                .iter()
+                .any(|t| t.to_lowercase() == tag.to_lowercase())
+            {
+                renamed.push(tag);
+            }
+        }
+
+        (renamed, changed)
+    }
+
+    /// Renames the tag in the file content, returns whether anything changed. `old` has to be
+    /// normalized, `new` is written as it is.
+    pub fn rename_in_content(content: &mut FileContent, old: &str, new: &str) -> bool {
+        match content {
+            FileContent::Note(note) => {
+                let body = note.get_body().clone();
+                let mut new_body = String::with_capacity(body.len());
+                let mut last = 0;
+                for (start, end) in Self::find_inline_tags(&body) {
+                    if let Some(tag) = Self::rename_tag(&body[start..end], old, new) {
+                        new_body.push_str(&body[last..start]);
+                        new_body.push_str(&tag);
+                        last = end;
+                    }
+                }
+                new_body.push_str(&body[last..]);
+                let mut changed = new_body != body;
+                note.set_body(new_body);
+
+                let mut frontmatter = match note.get_frontmatter() {
+                    Some(frontmatter) => frontmatter.clone(),
+                    None => return changed,
+                };
+                let tags = match frontmatter.get(FRONTMATTER_TAGS) {
+                    Some(tags) => tags.clone(),
+                    None => return changed,
+                };
+
+                let (renamed, tags_changed) =
+                    Self::rename_all(Self::frontmatter_tags(&tags), old, new);
+                if tags_changed {
+                    let value = match tags {
+                        Value::String(tags) if tags.contains(',') => {
+                            Value::String(renamed.join(", "))
+                        }
+                        Value::String(_) => Value::String(renamed.join(" ")),
+                        _ => Value::Sequence(renamed.into_iter().map(Value::String).collect()),
+                    };
+                    frontmatter
+                        .properties
+                        .insert(Value::String(FRONTMATTER_TAGS.to_string()), value);
+                    note.set_frontmatter(Some(frontmatter));
+                    changed = true;
+                }
+                changed
+            }
+            FileContent::TodoList(todo_list) => {
+                let mut changed = false;
+                for task in todo_list.get_tasks_mut() {
+                    let (renamed, task_changed) = Self::rename_all(task.get_tags(), old, new);
+                    if task_changed {
+                        task.set_tags(renamed);
+                        changed = true;
+                    }
+                }
+                changed
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Frontmatter;
+
+    fn id(id: &str) -> Id {
+        Id::from_string(id.to_string())
+    }
+
+    #[test]
+    fn test_find_inline_tags() {
+        let text = "# Heading\n#todo and #Project/Alpha/, not#this [[note#heading]] #123\n`#code` ```\n```\n#fenced\n```\n#last";
+        let tags: Vec<&str> = TagIndex::find_inline_tags(text)
+            .into_iter()
+            .map(|(start, end)| &text[start..end])
+            .collect();
+        assert_eq!(tags, vec!["todo", "Project/Alpha", "last"]);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut index = TagIndex::new();
+        index.insert_tags(&id("a.md"), vec!["project".into(), "work".into()]);
+        index.insert_tags(&id("b.md"), vec!["project/alpha".into()]);
+        index.insert_tags(&id("c.md"), vec!["projects".into()]);
+
+        assert_eq!(
+            index.get_files("#Project", false),
+            vec![id("a.md"), id("b.md")]
+        );
+        assert_eq!(index.get_files("proj", true).len(), 3);
+        assert_eq!(index.list().len(), 4);
+
+        index.rename_file(&id("a.md"), &id("x/a.md"));
+        index.delete_file(&id("b.md"));
+        assert_eq!(index.get_files("project", false), vec![id("x/a.md")]);
+        assert_eq!(index.list().len(), 3);
+    }
+
+    #[test]
+    fn test_rename_in_note() {
+        let (frontmatter, body) = Frontmatter::split(
+            "---\ntitle: x\ntags: [project/alpha, done]\n---\n#Project/Alpha #project-b\n",
+        );
+        let mut note = Note::new(body.to_string());
+        note.set_frontmatter(frontmatter);
+        let mut content = FileContent::Note(Box::new(note));
+
+        assert!(TagIndex::rename_in_content(&mut content, "project", "work"));
+        let note = match &content {
+            FileContent::Note(note) => note,
+            _ => unreachable!(),
+        };
+        assert_eq!(note.get_body(), "#work/Alpha #project-b\n");
+        assert_eq!(
+            TagIndex::get_note_tags(note),
+            vec!["work/alpha", "project-b", "work/alpha", "done"]
+        );
+
+        // merging into a tag the note already has
+        assert!(TagIndex::rename_in_content(
+            &mut content,
+            "done",
+            "work/alpha"
+        ));
+        let frontmatter = match &content {
+            FileContent::Note(note) => note.get_frontmatter().unwrap().to_yaml().unwrap(),
+            _ => unreachable!(),
+        };
+        assert_eq!(frontmatter, "title: x\ntags:\n- work/alpha\n");
+    }
+}
@@ -0,0 +1,420 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{Repeat, Task, TaskType, TodoList};
+
+/// Plain text formats todo lists are converted from and to
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq)]
+pub enum TaskFormat {
+    /// One task per line, see <https://github.com/todotxt/todo.txt>
+    TodoTxt,
+    /// `- [ ]` checklist items, nested items are subtasks
+    Markdown,
+}
+
+impl TaskFormat {
+    /// Descriptions, times of day and repeat weekdays have no place in either format and are
+    /// left out
+    pub fn export(&self, todo_list: &TodoList) -> String {
+        match self {
+            Self::TodoTxt => export_todo_txt(todo_list),
+            Self::Markdown => export_checklist(todo_list),
+        }
+    }
+
+    pub fn import(&self, content: &str) -> TodoList {
+        match self {
+            Self::TodoTxt => import_todo_txt(content),
+            Self::Markdown => import_checklist(content),
+        }
+    }
+}
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Spaces of indentation a tab counts for in checklists
+const TAB_WIDTH: usize = 4;
+const INDENT: &str = "  ";
+
+pub(super) fn format_date(date: &DateTime<Utc>) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+pub(super) fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDate::parse_from_str(value, DATE_FORMAT)
+        .ok()?
+        .and_hms_opt(0, 0, 0)
+        .map(|date| date.and_utc())
+}
+
+/// Letters run from `A` as the most important to `I`, which are noter priorities 9 to 1.
+/// Higher noter priorities are `A` as well and later letters are 1.
+fn format_priority(priority: u8) -> Option<char> {
+    match priority {
+        0 => None,
+        priority => Some((b'A' + 9 - priority.min(9)) as char),
+    }
+}
+
+fn parse_priority(value: &str) -> Option<u8> {
+    match value.as_bytes() {
+        [letter @ b'A'..=b'Z'] => Some(9 - (letter - b'A').min(8)),
+        _ => None,
+    }
+}
+
+/// Splits a leading `(A)` off the text
+pub(super) fn take_priority(text: &str) -> (Option<u8>, &str) {
+    let priority = text
+        .strip_prefix('(')
+        .and_then(|text| text.get(..2))
+        .and_then(|text| text.strip_suffix(')'))
+        .and_then(parse_priority);
+    match priority {
+        Some(priority) => (Some(priority), text[3..].trim_start()),
+        None => (None, text),
+    }
+}
+
+/// Splits a leading `2024-05-10` off the text
+fn take_date(text: &str) -> (Option<DateTime<Utc>>, &str) {
+    let (word, rest) = text.split_once(' ').unwrap_or((text, ""));
+    match parse_date(word) {
+        Some(date) => (Some(date), rest.trim_start()),
+        None => (None, text),
+    }
+}
+
+fn format_repeat(task: &Task) -> Option<String> {
+    let unit = match task.get_repeat()? {
+        Repeat::Daily => 'd',
+        Repeat::Weekly => 'w',
+        Repeat::Monthly => 'm',
+        Repeat::Yearly => 'y',
+    };
+    Some(format!("{}{}", task.get_repeat_interval(), unit))
+}
+
+/// Reads `1w`, `+2m` and the like, the strict `+` prefix of todo.txt has no counterpart
+fn parse_repeat(task: &mut Task, value: &str) -> bool {
+    let value = value.trim_start_matches('+');
+    let (interval, unit) = value.split_at(value.len().saturating_sub(1));
+    let repeat = match unit {
+        "d" => Repeat::Daily,
+        "w" => Repeat::Weekly,
+        "m" => Repeat::Monthly,
+        "y" => Repeat::Yearly,
+        _ => return false,
+    };
+    let interval = match interval {
+        "" => 1,
+        interval => match interval.parse::<u32>() {
+            Ok(interval) => interval.max(1),
+            Err(_) => return false,
+        },
+    };
+
+    task.set_repeat(Some(repeat), interval, Vec::new());
+    true
+}
+
+/// Reads the words of a task into its fields. The first `+project` is the project and
+/// `@context`s are tags, `due:`, `t:` (start), `rec:`, `pri:` and `done:` are read as well.
+/// Other words make up the content. `done:` is only kept for tasks marked as done before.
+pub(super) fn parse_words(task: &mut Task, text: &str) {
+    let mut content = Vec::new();
+    let mut tags = Vec::new();
+
+    for word in text.split_whitespace() {
+        if let Some(project) = word.strip_prefix('+').filter(|p| !p.is_empty()) {
+            if task.get_project().is_empty() {
+                task.set_project(project.to_string());
+                continue;
+            }
+        }
+        if let Some(tag) = word.strip_prefix('@').filter(|tag| !tag.is_empty()) {
+            tags.push(tag.to_string());
+            continue;
+        }
+
+        let read = match word.split_once(':') {
+            Some(("due", value)) => parse_date(value).map(|date| task.set_due_date(Some(date))),
+            Some(("t", value)) => parse_date(value).map(|date| task.set_start_date(Some(date))),
+            Some(("done", value)) => {
+                parse_date(value).map(|date| task.set_completion(task.is_completed(), Some(date)))
+            }
+            Some(("pri", value)) => {
+                parse_priority(value).map(|priority| task.set_priority(priority))
+            }
+            Some(("rec", value)) => parse_repeat(task, value).then_some(()),
+            _ => None,
+        };
+        if read.is_none() {
+            content.push(word);
+        }
+    }
+
+    task.set_content(content.join(" "));
+    task.set_tags(tags);
+}
+
+/// Content followed by the project, tags, dates and repeat of the task
+fn format_words(task: &Task) -> String {
+    let mut words = vec![task.get_content().clone()];
+    if !task.get_project().is_empty() {
+        words.push(format!("+{}", task.get_project().replace(' ', "-")));
+    }
+    words.extend(task.get_tags().iter().map(|tag| format!("@{}", tag)));
+    if let Some(due_date) = task.get_due_date() {
+        words.push(format!("due:{}", format_date(&due_date)));
+    }
+    if let Some(start_date) = task.get_start_date() {
+        words.push(format!("t:{}", format_date(&start_date)));
+    }
+    if let Some(repeat) = format_repeat(task) {
+        words.push(format!("rec:{}", repeat));
+    }
+    words.retain(|word| !word.is_empty());
+    words.join(" ")
+}
+
+fn format_todo_txt_line(task: &Task) -> String {
+    let mut line = String::new();
+    let priority = format_priority(task.get_priority());
+
+    match task.is_completed() {
+        true => {
+            line.push_str("x ");
+            // the creation date is only read after a completion date
+            let completed_at = task.get_completed_at().unwrap_or(task.get_created_at());
+            line.push_str(&format!("{} ", format_date(&completed_at)));
+        }
+        false => {
+            if let Some(priority) = priority {
+                line.push_str(&format!("({}) ", priority));
+            }
+        }
+    }
+    line.push_str(&format!("{} ", format_date(&task.get_created_at())));
+    line.push_str(&format_words(task));
+
+    // completed tasks keep their priority as a key, as the format asks
+    if let (true, Some(priority)) = (task.is_completed(), priority) {
+        line.push_str(&format!(" pri:{}", priority));
+    }
+    line
+}
+
+fn parse_todo_txt_line(line: &str, task_id: u32) -> Task {
+    let mut text = line.trim();
+    let mut task = Task::new(task_id, String::new());
+    if let Some(rest) = text.strip_prefix("x ") {
+        let (completed_at, rest) = take_date(rest.trim_start());
+        task.set_completion(true, completed_at);
+        text = rest;
+    } else {
+        let (priority, rest) = take_priority(text);
+        task.set_priority(priority.unwrap_or(0));
+        text = rest;
+    }
+    let (created_at, rest) = take_date(text);
+    if let Some(created_at) = created_at {
+        task.set_created_at(created_at);
+    }
+
+    parse_words(&mut task, rest);
+    task
+}
+
+pub fn export_todo_txt(todo_list: &TodoList) -> String {
+    todo_list
+        .get_tasks()
+        .iter()
+        .map(|task| format!("{}\n", format_todo_txt_line(task)))
+        .collect()
+}
+
+/// Every line is a task, todo.txt has no subtasks
+pub fn import_todo_txt(content: &str) -> TodoList {
+    let tasks = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| parse_todo_txt_line(line, i as u32 + 1))
+        .collect();
+    TodoList::new(tasks)
+}
+
+fn format_checklist_item(task: &Task, depth: usize) -> String {
+    let mark = if task.is_completed() { 'x' } else { ' ' };
+    let mut line = format!("{}- [{}] ", INDENT.repeat(depth), mark);
+    if let Some(priority) = format_priority(task.get_priority()) {
+        line.push_str(&format!("({}) ", priority));
+    }
+    line.push_str(&format_words(task));
+    if let (true, Some(completed_at)) = (task.is_completed(), task.get_completed_at()) {
+        line.push_str(&format!(" done:{}", format_date(&completed_at)));
+    }
+    line.push('\n');
+    line
+}
+
+fn export_subtasks(todo_list: &TodoList, parent_id: u32, depth: usize, lines: &mut String) {
+    // a cycle must not nest the tasks forever
+    if depth > todo_list.get_tasks().len() {
+        return;
+    }
+    for task in todo_list.get_tasks() {
+        if task.get_parent_id() == Some(parent_id) {
+            lines.push_str(&format_checklist_item(task, depth));
+            export_subtasks(todo_list, task.get_task_id(), depth + 1, lines);
+        }
+    }
+}
+
+/// Subtasks are written under their parent, one level deeper. Tasks whose parent is not in
+/// the list are written at the top level.
+pub fn export_checklist(todo_list: &TodoList) -> String {
+    let mut lines = String::new();
+    let roots = todo_list.get_tasks().iter().filter(|task| {
+        task.get_parent_id()
+            .is_none_or(|parent_id| todo_list.get_task(parent_id).is_err())
+    });
+    for root in roots {
+        lines.push_str(&format_checklist_item(root, 0));
+        export_subtasks(todo_list, root.get_task_id(), 1, &mut lines);
+    }
+    lines
+}
+
+/// Splits `- [x] text` into whether the item is checked and its text
+pub(super) fn parse_checklist_item(line: &str) -> Option<(bool, &str)> {
+    let item = line
+        .strip_prefix("- [")
+        .or_else(|| line.strip_prefix("* ["))
+        .or_else(|| line.strip_prefix("+ ["))?;
+    let mut chars = item.chars();
+    let checked = match chars.next()? {
+        ' ' => false,
+        'x' | 'X' => true,
+        _ => return None,
+    };
+    let text = chars.as_str().strip_prefix(']')?;
+    match text.is_empty() || text.starts_with(' ') {
+        true => Some((checked, text.trim())),
+        false => None,
+    }
+}
+
+fn get_indent(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+/// Reads the checklist items of a note, other lines are skipped. Items indented under
+/// another item are its subtasks.
+pub fn import_checklist(content: &str) -> TodoList {
+    let mut tasks: Vec<Task> = Vec::new();
+    // indentation and id of the items the next item may be nested in
+    let mut parents: Vec<(usize, u32)> = Vec::new();
+
+    for line in content.lines() {
+        let (checked, text) = match parse_checklist_item(line.trim_start()) {
+            Some(item) => item,
+            None => continue,
+        };
+        let indent = get_indent(line);
+        while parents.last().is_some_and(|(parent, _)| *parent >= indent) {
+            parents.pop();
+        }
+
+        let mut task = Task::new(tasks.len() as u32 + 1, String::new());
+        let (priority, text) = take_priority(text);
+        task.set_priority(priority.unwrap_or(0));
+        task.set_completion(checked, None);
+        parse_words(&mut task, text);
+        let parent_id = parents.last().map(|(_, id)| *id);
+        task.set_parent_id(parent_id);
+        if parent_id.is_some() {
+            task.set_task_type(TaskType::CheckList);
+        }
+
+        parents.push((indent, task.get_task_id()));
+        tasks.push(task);
+    }
+    TodoList::new(tasks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_todo_txt() {
+        let content = "(A) 2024-05-01 Call mom +family @phone due:2024-05-12 rec:2w\n\
+            x 2024-05-10 2024-05-02 Pay rent +home pri:C\n\
+            \n\
+            Water plants t:2024-05-03 key:value\n";
+        let todo_list = import_todo_txt(content);
+        let tasks = todo_list.get_tasks();
+        assert_eq!(tasks.len(), 3);
+
+        assert_eq!(tasks[0].get_content(), "Call mom");
+        assert_eq!(tasks[0].get_priority(), 9);
+        assert_eq!(tasks[0].get_project(), "family");
+        assert_eq!(tasks[0].get_tags(), vec!["phone"]);
+        assert_eq!(tasks[0].get_due_date(), parse_date("2024-05-12"));
+        assert_eq!(tasks[0].get_created_at(), parse_date("2024-05-01").unwrap());
+        assert_eq!(tasks[0].get_repeat(), Some(Repeat::Weekly));
+        assert_eq!(tasks[0].get_repeat_interval(), 2);
+
+        assert!(tasks[1].is_completed());
+        assert_eq!(tasks[1].get_completed_at(), parse_date("2024-05-10"));
+        assert_eq!(tasks[1].get_priority(), 7);
+        assert_eq!(tasks[2].get_content(), "Water plants key:value");
+        assert_eq!(tasks[2].get_start_date(), parse_date("2024-05-03"));
+
+        assert_eq!(
+            export_todo_txt(&todo_list),
+            "(A) 2024-05-01 Call mom +family @phone due:2024-05-12 rec:2w\n\
+            x 2024-05-10 2024-05-02 Pay rent +home pri:C\n"
+                .to_string()
+                + &format!(
+                    "{} Water plants key:value t:2024-05-03\n",
+                    format_date(&tasks[2].get_created_at())
+                )
+        );
+    }
+
+    #[test]
+    fn test_checklist() {
+        let content = "# Trip\n\
+            - [ ] (B) Pack +travel due:2024-06-01\n\
+            \x20 - [x] Socks done:2024-05-30\n\
+            \x20 - [ ] Charger\n\
+            \t* [ ] Cable\n\
+            Some text\n\
+            - [ ] Book hotel\n\
+            - [] not an item\n";
+        let todo_list = import_checklist(content);
+        let tasks = todo_list.get_tasks();
+        let parents: Vec<Option<u32>> = tasks.iter().map(|task| task.get_parent_id()).collect();
+        assert_eq!(parents, vec![None, Some(1), Some(1), Some(3), None]);
+        assert_eq!(tasks[0].get_task_type(), TaskType::Task);
+        assert_eq!(tasks[1].get_task_type(), TaskType::CheckList);
+        assert_eq!(tasks[0].get_priority(), 8);
+        assert!(tasks[1].is_completed());
+        assert_eq!(tasks[1].get_completed_at(), parse_date("2024-05-30"));
+
+        assert_eq!(
+            export_checklist(&todo_list),
+            "- [ ] (B) Pack +travel due:2024-06-01\n\
+            \x20 - [x] Socks done:2024-05-30\n\
+            \x20 - [ ] Charger\n\
+            \x20   - [ ] Cable\n\
+            - [ ] Book hotel\n"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use super::{find_inline_tasks, File, FileContent, FileType, Id, Reminder, Task};
+
+/// Open task of a todo list or a note together with the file it is in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgendaTask {
+    pub source: Id,
+    /// Line of the note body the task is written on, tasks of todo lists have none
+    pub line: Option<usize>,
+    pub task: Task,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AgendaFilter {
+    pub project: Option<String>,
+    pub priority: Option<u8>,
+}
+
+impl AgendaFilter {
+    fn matches(&self, task: &Task) -> bool {
+        let project = self
+            .project
+            .as_ref()
+            .is_none_or(|project| task.get_project().eq_ignore_ascii_case(project));
+        let priority = self
+            .priority
+            .is_none_or(|priority| task.get_priority() == priority);
+        project && priority
+    }
+}
+
+/// Open tasks grouped by their due date
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Agenda {
+    pub overdue: Vec<AgendaTask>,
+    pub today: Vec<AgendaTask>,
+    pub upcoming: Vec<AgendaTask>,
+    pub no_date: Vec<AgendaTask>,
+}
+
+/// Open tasks of every todo list and note in the vault, so the agenda never has to read the
+/// files. Tasks of notes have their line as task id.
+#[derive(Default)]
+pub struct TaskIndex {
+    files: HashMap<Id, Vec<Task>>,
+}
+
+impl TaskIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_file_tasks(file: &File) -> Vec<Task> {
+        match &file.content {
+            FileContent::TodoList(todo_list) => todo_list
+                .get_tasks()
+                .iter()
+                .filter(|task| !task.is_completed())
+                .cloned()
+                .collect(),
+            FileContent::Note(note) => find_inline_tasks(note.get_body())
+                .into_iter()
+                .map(|inline| inline.task)
+                .filter(|task| !task.is_completed())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn insert_file(&mut self, file: &File) {
+        self.insert_tasks(&file.id, Self::get_file_tasks(file));
+    }
+
+    pub fn insert_tasks(&mut self, id: &Id, tasks: Vec<Task>) {
+        match tasks.is_empty() {
+            true => self.files.remove(id),
+            false => self.files.insert(id.clone(), tasks),
+        };
+    }
+
+    pub fn delete_file(&mut self, id: &Id) {
+        self.files.remove(id);
+    }
+
+    pub fn rename_file(&mut self, id: &Id, new_id: &Id) {
+        if let Some(tasks) = self.files.remove(id) {
+            self.files.insert(new_id.clone(), tasks);
+        }
+    }
+
+    pub fn get_tasks(&self, id: &Id) -> Option<&Vec<Task>> {
+        self.files.get(id)
+    }
+
+    /// Reminders of all open tasks
+    pub fn get_reminders(&self) -> Vec<Reminder> {
+        self.files
+            .iter()
+            .flat_map(|(source, tasks)| {
+                tasks.iter().filter_map(move |task| {
+                    Some(Reminder {
+                        source: source.clone(),
+                        task_id: task.get_task_id(),
+                        content: task.get_content().clone(),
+                        at: task.get_reminder()?,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Groups the open tasks into overdue, due `today`, due in the following `days` days and
+    /// without a due date, tasks due later are left out. Due dates with a time are compared as
+    /// days in `tz`.
+    pub fn agenda<Tz: TimeZone>(
+        &self,
+        today: NaiveDate,
+        tz: &Tz,
+        days: u32,
+        filter: &AgendaFilter,
+    ) -> Agenda {
+        let mut agenda = Agenda::default();
+        let until = today
+            .checked_add_days(chrono::Days::new(days.into()))
+            .unwrap_or(NaiveDate::MAX);
+
+        for (source, tasks) in &self.files {
+            let is_note = source.get_type() == Some(FileType::Note);
+            for task in tasks.iter().filter(|task| filter.matches(task)) {
+                let item = AgendaTask {
+                    source: source.clone(),
+                    line: is_note.then_some(task.get_task_id() as usize),
+                    task: task.clone(),
+                };
+                let due = match task.get_due_day(tz) {
+                    Some(due) => due,
+                    None => {
+                        agenda.no_date.push(item);
+                        continue;
+                    }
+                };
+
+                match due {
+                    due if due < today => agenda.overdue.push(item),
+                    due if due == today => agenda.today.push(item),
+                    due if due <= until => agenda.upcoming.push(item),
+                    _ => {}
+                }
+            }
+        }
+
+        for group in [
+            &mut agenda.overdue,
+            &mut agenda.today,
+            &mut agenda.upcoming,
+            &mut agenda.no_date,
+        ] {
+            group.sort_by(|a, b| {
+                a.task
+                    .get_due_date()
+                    .cmp(&b.task.get_due_date())
+                    .then(b.task.get_priority().cmp(&a.task.get_priority()))
+                    .then(a.source.as_str().cmp(b.source.as_str()))
+            });
+        }
+        agenda
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileSerializable, TodoList};
+    use chrono::{FixedOffset, Utc};
+
+    #[test]
+    fn test_agenda() {
+        let csv = "task_id,content,due_date,project,priority,is_completed\n\
+            1,Overdue,2024-05-01,work,1,false\n\
+            2,Done,2024-05-01,work,1,true\n\
+            3,Today,2024-05-10T18:00:00Z,home,2,false\n\
+            4,Soon,2024-05-12,work,3,false\n\
+            5,Later,2024-06-01,work,0,false\n\
+            6,Someday,,home,0,false\n";
+        let todo_list = TodoList::custom_deserialize(csv.as_bytes()).unwrap();
+        let tasks: Vec<Task> = todo_list
+            .get_tasks()
+            .iter()
+            .filter(|task| !task.is_completed())
+            .cloned()
+            .collect();
+
+        let mut index = TaskIndex::new();
+        index.insert_tasks(&Id::from_string("a.todo.csv".to_string()), tasks);
+
+        let today = NaiveDate::from_ymd_opt(2024, 5, 10).unwrap();
+        let content = |tasks: &Vec<AgendaTask>| -> Vec<String> {
+            tasks
+                .iter()
+                .map(|task| task.task.get_content().clone())
+                .collect()
+        };
+
+        let agenda = index.agenda(today, &Utc, 7, &AgendaFilter::default());
+        assert_eq!(content(&agenda.overdue), vec!["Overdue"]);
+        assert_eq!(content(&agenda.today), vec!["Today"]);
+        assert_eq!(content(&agenda.upcoming), vec!["Soon"]);
+        assert_eq!(content(&agenda.no_date), vec!["Someday"]);
+
+        let filter = AgendaFilter {
+            project: Some("Work".to_string()),
+            priority: None,
+        };
+        let agenda = index.agenda(today, &Utc, 30, &filter);
+        assert_eq!(content(&agenda.upcoming), vec!["Soon", "Later"]);
+        assert!(agenda.today.is_empty() && agenda.no_date.is_empty());
+
+        // days past the last date chrono knows reach up to it
+        let agenda = index.agenda(today, &Utc, u32::MAX, &AgendaFilter::default());
+        assert_eq!(content(&agenda.upcoming), vec!["Soon", "Later"]);
+
+        // date-only due dates keep their day west of UTC, 18:00 UTC is still the 10th there
+        let tz = FixedOffset::west_opt(5 * 3600).unwrap();
+        let agenda = index.agenda(today, &tz, 7, &AgendaFilter::default());
+        assert_eq!(content(&agenda.overdue), vec!["Overdue"]);
+        assert_eq!(content(&agenda.today), vec!["Today"]);
+        assert_eq!(content(&agenda.upcoming), vec!["Soon"]);
+
+        // while 18:00 UTC is already the 11th east of it
+        let tz = FixedOffset::east_opt(9 * 3600).unwrap();
+        let agenda = index.agenda(today, &tz, 7, &AgendaFilter::default());
+        assert!(agenda.today.is_empty());
+        assert_eq!(content(&agenda.upcoming), vec!["Today", "Soon"]);
+    }
+}
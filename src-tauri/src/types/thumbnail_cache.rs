@@ -0,0 +1,147 @@
+use anyhow::{anyhow, Result};
+use std::{
+    fs::{self, create_dir_all},
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+use super::{FileStamp, FileType, Id, APP_DIR};
+
+const THUMBNAIL_DIR: &str = "thumbnails";
+
+/// Longest side of a thumbnail in pixels, the aspect ratio is kept
+pub const THUMBNAIL_SIZE: u32 = 256;
+
+/// Downscaled png copies of the images in the vault. A thumbnail is named after the id and the
+/// mtime of its source, so a changed source never matches an old thumbnail.
+#[derive(Clone)]
+pub struct ThumbnailCache {
+    dir: PathBuf,
+}
+
+impl ThumbnailCache {
+    pub fn new(notes_dir: &Path) -> Self {
+        Self {
+            dir: notes_dir.join(APP_DIR).join(THUMBNAIL_DIR),
+        }
+    }
+
+    /// Svg images are already small and scale by themselves, they are shown as they are
+    pub fn supports(id: &Id) -> bool {
+        id.get_type() == Some(FileType::Image) && !id.as_str().to_lowercase().ends_with(".svg")
+    }
+
+    /// 64 bit FNV-1a hash of the id. The names stay on disk across builds, so the hash must not
+    /// change with the Rust version like the hasher of the std does.
+    fn get_prefix(id: &Id) -> String {
+        let hash = id
+            .as_str()
+            .bytes()
+            .fold(0xcbf29ce484222325, |hash: u64, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+            });
+        format!("{:016x}-", hash)
+    }
+
+    pub fn get_path(&self, id: &Id, stamp: &FileStamp) -> PathBuf {
+        self.dir.join(format!(
+            "{}{}.png",
+            Self::get_prefix(id),
+            stamp.modified_at.timestamp_millis()
+        ))
+    }
+
+    /// Path of the thumbnail if it was generated for the current version of the source
+    pub fn get(&self, id: &Id, stamp: &FileStamp) -> Option<PathBuf> {
+        let path = self.get_path(id, stamp);
+        path.is_file().then_some(path)
+    }
+
+    pub fn generate(&self, id: &Id, source: &Path, stamp: &FileStamp) -> Result<PathBuf> {
+        if !Self::supports(id) {
+            return Err(anyhow!("No thumbnails for this file type"));
+        }
+
+        let image = image::load_from_memory(&fs::read(source)?)?;
+        let mut content = Vec::new();
+        image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).write_to(
+            &mut Cursor::new(&mut content),
+            image::ImageOutputFormat::Png,
+        )?;
+
+        self.invalidate(id)?;
+        create_dir_all(&self.dir)?;
+
+        // written to a temporary file first so a half written thumbnail is never served
+        let path = self.get_path(id, stamp);
+        let tmp_path = path.with_extension("png.tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(path)
+    }
+
+    /// Removes every thumbnail of the file, called when the file changed, moved or was deleted
+    pub fn invalidate(&self, id: &Id) -> Result<()> {
+        if !Self::supports(id) {
+            return Ok(());
+        }
+
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(()),
+        };
+
+        let prefix = Self::get_prefix(id);
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                fs::remove_file(entry.path())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stable_prefix() {
+        let prefix = |id: &str| ThumbnailCache::get_prefix(&Id::from_string(id.to_string()));
+        assert_eq!(prefix(""), "cbf29ce484222325-");
+        assert_eq!(prefix("a"), "af63dc4c8601ec8c-");
+        assert_ne!(prefix("img/a.png"), prefix("img/b.png"));
+    }
+
+    #[test]
+    fn test_generate() {
+        let notes_dir =
+            std::env::temp_dir().join(format!("noter-thumbnails-{}", std::process::id()));
+        fs::create_dir_all(&notes_dir).unwrap();
+        let source = notes_dir.join("wide.png");
+        image::RgbImage::new(1024, 512).save(&source).unwrap();
+
+        let cache = ThumbnailCache::new(&notes_dir);
+        let id = Id::from_string("wide.png".to_string());
+        let stamp = FileStamp::from_metadata(&fs::metadata(&source).unwrap()).unwrap();
+        assert!(cache.get(&id, &stamp).is_none());
+
+        let path = cache.generate(&id, &source, &stamp).unwrap();
+        assert_eq!(cache.get(&id, &stamp), Some(path.clone()));
+        assert_eq!(image::image_dimensions(&path).unwrap(), (256, 128));
+
+        // a newer version of the source replaces the old thumbnail
+        let newer = FileStamp {
+            modified_at: stamp.modified_at + chrono::Duration::seconds(1),
+            ..stamp
+        };
+        assert!(cache.get(&id, &newer).is_none());
+        cache.generate(&id, &source, &newer).unwrap();
+        assert!(!path.exists());
+
+        cache.invalidate(&id).unwrap();
+        assert!(cache.get(&id, &newer).is_none());
+        fs::remove_dir_all(&notes_dir).unwrap();
+    }
+}
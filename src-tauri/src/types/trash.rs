@@ -0,0 +1,260 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, create_dir_all},
+    path::{Path, PathBuf},
+};
+
+use super::Id;
+
+pub const TRASH_DIR: &str = ".trash";
+const TRASH_MANIFEST: &str = "trash.json";
+
+/// Record of an entry moved to the trash, `trash_id` is also the name it is stored under
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub trash_id: String,
+    pub original_id: Id,
+    pub is_dir: bool,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Vault-local trash folder, deleted files are moved here instead of being removed
+pub struct Trash {
+    dir: PathBuf,
+}
+
+impl Trash {
+    pub fn new(notes_dir: &Path) -> Self {
+        Self {
+            dir: notes_dir.join(TRASH_DIR),
+        }
+    }
+
+    fn get_manifest_path(&self) -> PathBuf {
+        self.dir.join(TRASH_MANIFEST)
+    }
+
+    pub fn get_path(&self, trash_id: &str) -> PathBuf {
+        self.dir.join(trash_id)
+    }
+
+    pub fn list(&self) -> Result<Vec<TrashEntry>> {
+        let path = self.get_manifest_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let raw = fs::read(&path)?;
+        let entries: Vec<TrashEntry> = serde_json::from_slice(&raw)?;
+        Ok(entries)
+    }
+
+    fn store(&self, entries: &[TrashEntry]) -> Result<()> {
+        create_dir_all(&self.dir)?;
+        fs::write(
+            self.get_manifest_path(),
+            serde_json::to_vec_pretty(entries)?,
+        )?;
+        Ok(())
+    }
+
+    /// Moves the file or directory at `path` into the trash
+    pub fn put(&self, path: &Path, original_id: &Id) -> Result<TrashEntry> {
+        create_dir_all(&self.dir)?;
+
+        let deleted_at = Utc::now();
+        let name = path
+            .file_name()
+            .ok_or_else(|| anyhow!("Could not read file name"))?
+            .to_string_lossy()
+            .to_string();
+
+        let mut trash_id = format!("{}-{}", deleted_at.timestamp_millis(), name);
+        let mut n = 1;
+        while self.get_path(&trash_id).exists() {
+            trash_id = format!("{}-{}-{}", deleted_at.timestamp_millis(), n, name);
+            n += 1;
+        }
+
+        let entry = TrashEntry {
+            trash_id,
+            original_id: original_id.clone(),
+            is_dir: path.is_dir(),
+            deleted_at,
+        };
+
+        // deleting must keep working when the manifest was broken, by a sync conflict say
+        let mut entries = self.list().unwrap_or_else(|e| {
+            println!("Trash manifest is corrupted, starting a new one; {:?}", e);
+            Vec::new()
+        });
+        entries.push(entry.clone());
+
+        let trash_path = self.get_path(&entry.trash_id);
+        fs::rename(path, &trash_path)?;
+        // an entry missing from the manifest could never be restored, so it is put back
+        if let Err(e) = self.store(&entries) {
+            fs::rename(&trash_path, path)?;
+            return Err(e);
+        }
+
+        Ok(entry)
+    }
+
+    /// Moves the entry out of the trash to `target`
+    pub fn take(&self, trash_id: &str, target: &Path) -> Result<TrashEntry> {
+        let mut entries = self.list()?;
+        let position = entries
+            .iter()
+            .position(|e| e.trash_id == trash_id)
+            .ok_or_else(|| anyhow!("Entry is not in trash"))?;
+
+        if let Some(parent) = target.parent() {
+            create_dir_all(parent)?;
+        }
+        let trash_path = self.get_path(trash_id);
+        fs::rename(&trash_path, target)?;
+
+        let entry = entries.remove(position);
+        // the manifest still lists the entry, so it goes back to where it is listed
+        if let Err(e) = self.store(&entries) {
+            fs::rename(target, &trash_path)?;
+            return Err(e);
+        }
+        Ok(entry)
+    }
+
+    pub fn find(&self, trash_id: &str) -> Result<TrashEntry> {
+        self.list()?
+            .into_iter()
+            .find(|e| e.trash_id == trash_id)
+            .ok_or_else(|| anyhow!("Entry is not in trash"))
+    }
+
+    fn remove(&self, entry: &TrashEntry) -> Result<()> {
+        let path = self.get_path(&entry.trash_id);
+        match path.is_dir() {
+            true => fs::remove_dir_all(&path)?,
+            false if path.exists() => fs::remove_file(&path)?,
+            false => (),
+        };
+        Ok(())
+    }
+
+    pub fn empty(&self) -> Result<Vec<TrashEntry>> {
+        self.purge(|_| true)
+    }
+
+    /// Permanently deletes entries that have been in the trash for longer than `age`
+    pub fn purge_older_than(&self, age: Duration) -> Result<Vec<TrashEntry>> {
+        let limit = Utc::now() - age;
+        self.purge(|entry| entry.deleted_at < limit)
+    }
+
+    fn purge<F: Fn(&TrashEntry) -> bool>(&self, f: F) -> Result<Vec<TrashEntry>> {
+        let (purged, kept): (Vec<TrashEntry>, Vec<TrashEntry>) =
+            self.list()?.into_iter().partition(|entry| f(entry));
+
+        if purged.is_empty() {
+            return Ok(purged);
+        }
+
+        for entry in &purged {
+            self.remove(entry)?;
+        }
+        self.store(&kept)?;
+        Ok(purged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("noter-trash-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("notes")).unwrap();
+        fs::write(dir.join("a.md"), "a").unwrap();
+        fs::write(dir.join("notes/a.md"), "nested a").unwrap();
+        dir
+    }
+
+    fn id(id: &str) -> Id {
+        Id::from_string(id.to_string())
+    }
+
+    #[test]
+    fn test_put_and_take() {
+        let dir = vault("put");
+        let trash = Trash::new(&dir);
+
+        let first = trash.put(&dir.join("a.md"), &id("a.md")).unwrap();
+        let second = trash
+            .put(&dir.join("notes/a.md"), &id("notes/a.md"))
+            .unwrap();
+        let folder = trash.put(&dir.join("notes"), &id("notes")).unwrap();
+        // entries with the same name deleted at once are stored apart
+        assert_ne!(first.trash_id, second.trash_id);
+        assert!(!dir.join("a.md").exists());
+        assert!(folder.is_dir);
+        assert_eq!(trash.list().unwrap().len(), 3);
+
+        let taken = trash.take(&second.trash_id, &dir.join("b.md")).unwrap();
+        assert_eq!(taken.original_id, id("notes/a.md"));
+        assert_eq!(fs::read_to_string(dir.join("b.md")).unwrap(), "nested a");
+        assert!(trash.find(&second.trash_id).is_err());
+        assert!(trash.take(&second.trash_id, &dir.join("c.md")).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_purge() {
+        let dir = vault("purge");
+        let trash = Trash::new(&dir);
+
+        let old = trash.put(&dir.join("a.md"), &id("a.md")).unwrap();
+        let recent = trash.put(&dir.join("notes"), &id("notes")).unwrap();
+        let mut entries = trash.list().unwrap();
+        entries[0].deleted_at = Utc::now() - Duration::days(40);
+        trash.store(&entries).unwrap();
+
+        let purged = trash.purge_older_than(Duration::days(30)).unwrap();
+        assert_eq!(purged.len(), 1);
+        assert_eq!(purged[0].trash_id, old.trash_id);
+        assert!(!trash.get_path(&old.trash_id).exists());
+        assert!(trash.get_path(&recent.trash_id).exists());
+
+        let purged = trash.empty().unwrap();
+        assert_eq!(purged.len(), 1);
+        assert!(!trash.get_path(&recent.trash_id).exists());
+        assert!(trash.list().unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_broken_manifest() {
+        let dir = vault("manifest");
+        let trash = Trash::new(&dir);
+
+        fs::create_dir_all(dir.join(TRASH_DIR)).unwrap();
+        fs::write(trash.get_manifest_path(), "{ not json").unwrap();
+        assert!(trash.list().is_err());
+        let entry = trash.put(&dir.join("a.md"), &id("a.md")).unwrap();
+        assert_eq!(trash.list().unwrap()[0].trash_id, entry.trash_id);
+
+        // an entry that can not be recorded is put back
+        fs::remove_file(trash.get_manifest_path()).unwrap();
+        fs::create_dir(trash.get_manifest_path()).unwrap();
+        assert!(trash
+            .put(&dir.join("notes/a.md"), &id("notes/a.md"))
+            .is_err());
+        assert!(dir.join("notes/a.md").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
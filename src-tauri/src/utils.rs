@@ -1,7 +1,7 @@
 use crate::{state::*, Config};
 
 use anyhow::Result;
-use tauri::{AppHandle, Manager, WindowBuilder};
+use tauri::{AppHandle, Manager, WindowBuilder, WindowEvent};
 
 pub fn run_welcome(app: AppHandle) {
     let handle = std::thread::spawn(move || {
@@ -27,6 +27,14 @@ pub fn run_welcome(app: AppHandle) {
 
 pub fn startup(config: Config, app: AppHandle, state: NoterState) {
     let (width, height) = config.get_window_size();
+    let notes_dir = state.get_notes_dir().clone();
+    let file_manager = state.get_file_manager().clone();
+
+    if let Some(days) = config.get_trash_retention_days() {
+        if let Err(e) = state.purge_trash(days) {
+            println!("Could not purge trash; {:?}", e);
+        }
+    }
 
     let handle = std::thread::spawn(move || {
         let window = WindowBuilder::new(
@@ -44,6 +52,33 @@ pub fn startup(config: Config, app: AppHandle, state: NoterState) {
         .build()
         .unwrap();
         window.manage(HeldState::new(state));
+        window.manage(ThumbnailWorker::new(app.clone(), file_manager));
+        window.manage(ReminderScheduler::new(notes_dir.clone()));
+        ReminderScheduler::spawn(app.clone());
+
+        let app_handle = app.clone();
+        window.on_window_event(move |event| {
+            if let WindowEvent::Destroyed = event {
+                let state = app_handle.state::<HeldState>();
+                let result = match state.lock() {
+                    Ok(state) => state.store_cache(),
+                    Err(_) => Err(anyhow::anyhow!("Could not lock state")),
+                };
+                if let Err(e) = result {
+                    println!("Could not store index cache; {:?}", e);
+                }
+                if let Err(e) = app_handle.state::<ReminderScheduler>().store() {
+                    println!("Could not store reminder schedule; {:?}", e);
+                }
+            }
+        });
+
+        match VaultWatcher::new(app.clone(), notes_dir) {
+            Ok(watcher) => {
+                window.manage(watcher);
+            }
+            Err(e) => println!("Could not watch notes dir; {:?}", e),
+        };
     });
 
     let _ = handle.join();
@@ -0,0 +1,13 @@
+import { listen } from "@tauri-apps/api/event";
+import { useNoterState } from "@/state/state";
+import { Reminder } from "@/types/reminder";
+
+/**
+ * Notifications can not report clicks, so due reminders are also listed in the app, where
+ * the user opens their task. The window is left alone, reminders must not take the focus.
+ */
+export default async function handleReminders() {
+  await listen<Reminder>("reminder", ({ payload }) => {
+    useNoterState.getState().addReminder(payload);
+  });
+}
@@ -6,6 +6,7 @@ import refresh from "@/types/commands/refresh";
 import { File } from "@/types/files/file";
 import getFile from "@/types/commands/getFile";
 import { Directory } from "@/types/files/directory";
+import { Reminder } from "@/types/reminder";
 
 type Fields = {
   files: FilePreview[];
@@ -14,6 +15,10 @@ type Fields = {
   open: FilePreview | null;
   isSidebarOpen: boolean;
   metadata: Metadata;
+  /** Task to scroll to and highlight in the open todo list, set when a reminder is opened */
+  focusedTask: { source: Id; task_id: number } | null;
+  /** Due reminders the user has not opened or dismissed yet */
+  reminders: Reminder[];
 };
 
 type Actions = {
@@ -21,6 +26,9 @@ type Actions = {
   addSelected: (id: Id) => Fields["selected"];
   removeSelected: (id: Id) => Fields["selected"];
   setOpen: (id: Id | null) => Promise<File | null>;
+  openTask: (source: Id, taskId: number) => Promise<File | null>;
+  addReminder: (reminder: Reminder) => void;
+  dismissReminder: (reminder: Reminder) => void;
   toggleSidebar: () => void;
   refresh: () => Promise<[Metadata, FilePreview[]]>;
 };
@@ -34,6 +42,8 @@ export const useNoterState = create<Fields & Actions>((set, get) => ({
   selected: [],
   open: null,
   isSidebarOpen: true,
+  focusedTask: null,
+  reminders: [],
   setSelected: (files) => {
     set({ selected: files });
     return files;
@@ -86,6 +96,23 @@ export const useNoterState = create<Fields & Actions>((set, get) => ({
     set({ open: filePreview });
     return file;
   },
+  openTask: async (source, taskId) => {
+    const file = await get().setOpen(source);
+    set({ focusedTask: file ? { source, task_id: taskId } : null });
+    return file;
+  },
+  addReminder: (reminder) => {
+    // a task only keeps its latest reminder
+    get().dismissReminder(reminder);
+    set({ reminders: [...get().reminders, reminder] });
+  },
+  dismissReminder: (reminder) => {
+    set({
+      reminders: get().reminders.filter(
+        (r) => r.source !== reminder.source || r.task_id !== reminder.task_id,
+      ),
+    });
+  },
   toggleSidebar: () => {
     set({ isSidebarOpen: !get().isSidebarOpen });
   },
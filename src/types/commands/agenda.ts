@@ -0,0 +1,25 @@
+import { invoke } from "@tauri-apps/api";
+import { Task } from "../files/todo-list";
+import { Id } from "../id";
+
+export type AgendaTask = {
+  source: Id;
+  /** Line of the note body for tasks written in notes */
+  line: number | null;
+  task: Task;
+};
+
+export type Agenda = {
+  overdue: AgendaTask[];
+  today: AgendaTask[];
+  upcoming: AgendaTask[];
+  no_date: AgendaTask[];
+};
+
+export default async function agenda(args: {
+  days?: number;
+  project?: string;
+  priority?: number;
+}): Promise<Agenda> {
+  return invoke<Agenda>("agenda", args);
+}
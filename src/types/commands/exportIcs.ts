@@ -0,0 +1,14 @@
+import { invoke } from "@tauri-apps/api";
+import { save } from "@tauri-apps/api/dialog";
+import { Id } from "../id";
+
+/** Asks where to save and exports the todo list, or every todo list without an id */
+export default async function exportIcs(id?: Id): Promise<boolean> {
+  const path = await save({
+    filters: [{ name: "iCalendar", extensions: ["ics"] }],
+  });
+  if (!path) return false;
+
+  await invoke<void>("export_ics", { path, id });
+  return true;
+}
@@ -0,0 +1,24 @@
+import { invoke } from "@tauri-apps/api";
+import { save } from "@tauri-apps/api/dialog";
+import { Id } from "../id";
+
+export type TaskFormat = "TodoTxt" | "Markdown";
+
+const EXTENSIONS: Record<TaskFormat, string> = {
+  TodoTxt: "txt",
+  Markdown: "md",
+};
+
+/** Asks where to save and exports the todo list in the format */
+export default async function exportTasks(
+  id: Id,
+  format: TaskFormat,
+): Promise<boolean> {
+  const path = await save({
+    filters: [{ name: format, extensions: [EXTENSIONS[format]] }],
+  });
+  if (!path) return false;
+
+  await invoke<void>("export_tasks", { path, id, format });
+  return true;
+}
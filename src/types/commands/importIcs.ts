@@ -0,0 +1,13 @@
+import { invoke } from "@tauri-apps/api";
+import { File } from "../files/file";
+import { Id } from "../id";
+
+export default async function importIcs(
+  path: string,
+  parentId: Id | null,
+): Promise<File> {
+  return invoke<File>("import_ics", {
+    path,
+    parentId: parentId ? parentId : undefined,
+  });
+}
@@ -0,0 +1,16 @@
+import { invoke } from "@tauri-apps/api";
+import { File } from "../files/file";
+import { Id } from "../id";
+import { TaskFormat } from "./exportTasks";
+
+export default async function importTasks(
+  path: string,
+  parentId: Id | null,
+  format: TaskFormat,
+): Promise<File> {
+  return invoke<File>("import_tasks", {
+    path,
+    parentId: parentId ? parentId : undefined,
+    format,
+  });
+}
@@ -7,11 +7,13 @@ export default async function putFile(
   args: {
     name?: string;
     body?: string;
+    properties?: Record<string, unknown>;
   },
 ) {
   return invoke<File>("put_file", {
     id,
     name: args.name,
     content: args.body,
+    properties: args.properties,
   });
 }
@@ -0,0 +1,8 @@
+import { invoke } from "@tauri-apps/api";
+import { File } from "../files/file";
+import { Id } from "../id";
+
+/** Appends a task like "Pay invoice tomorrow 5pm !2 +finance every month" to the todo list */
+export default async function quickAddTask(id: Id, text: string): Promise<File> {
+  return invoke<File>("quick_add_task", { id, text });
+}
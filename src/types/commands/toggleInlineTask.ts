@@ -0,0 +1,10 @@
+import { invoke } from "@tauri-apps/api";
+import { File } from "../files/file";
+import { Id } from "../id";
+
+export default async function toggleInlineTask(
+  id: Id,
+  line: number,
+): Promise<File> {
+  return invoke<File>("toggle_inline_task", { id, line });
+}
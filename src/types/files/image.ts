@@ -1 +1,14 @@
-export type Image = {};
+export type ImageExif = {
+  captured_at: string | null;
+  orientation: number | null;
+  camera_make: string | null;
+  camera_model: string | null;
+};
+
+export type Image = {
+  format: string | null;
+  width: number | null;
+  height: number | null;
+  size: number;
+  exif: ImageExif | null;
+};
@@ -1,3 +1,9 @@
+export type Frontmatter = {
+  properties: Record<string, unknown>;
+  raw: string;
+};
+
 export type Note = {
+  frontmatter: Frontmatter | null;
   body: string;
 };
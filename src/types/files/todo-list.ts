@@ -1 +1,44 @@
-export type TodoList = {};
+import { Id } from "../id";
+
+export type Repeat = "Daily" | "Weekly" | "Monthly" | "Yearly";
+
+export type Weekday = "Mon" | "Tue" | "Wed" | "Thu" | "Fri" | "Sat" | "Sun";
+
+export type TaskType = "Task" | "CheckList";
+
+export type Task = {
+  task_id: number;
+  parent_id: number | null;
+  task_type: TaskType;
+  content: string;
+  description: string;
+  project: string;
+  attachment: Id | null;
+  priority: number;
+  is_completed: boolean;
+  created_at: string;
+  completed_at: string | null;
+  start_date: string | null;
+  due_date: string | null;
+  reminder: string | null;
+  repeat: Repeat | null;
+  /** Every n days, weeks, months or years */
+  repeat_interval: number;
+  repeat_weekdays: Weekday[];
+  tags: string;
+  /** Values of csv columns the app does not know, written back unchanged */
+  extra: Record<string, string>;
+};
+
+/** Task with its subtasks, the tasks themselves are in the flat list */
+export type TaskNode = {
+  task_id: number;
+  subtasks: TaskNode[];
+};
+
+export type TodoList = {
+  tasks: Task[];
+  extra_columns: string[];
+  /** Derived from the parent ids, ignored when the list is saved */
+  tree?: TaskNode[];
+};
@@ -1,5 +1,17 @@
+import { convertFileSrc } from "@tauri-apps/api/tauri";
+
 export type Id = string;
 
 export function getParentId(id: Id): Id {
   return id.split("/").slice(0, -1).join("/");
 }
+
+/** Url the webview can load the file from, e.g. for `![[image.png]]` embeds */
+export function getVaultUrl(id: Id): string {
+  return convertFileSrc(id, "noter");
+}
+
+/** Downscaled png of an image, only served once `request_thumbnails` reported it ready */
+export function getThumbnailUrl(id: Id): string {
+  return `${getVaultUrl(id)}?thumbnail`;
+}
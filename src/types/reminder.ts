@@ -0,0 +1,9 @@
+import { Id } from "./id";
+
+/** Due reminder of a task, delivered with the `reminder` event */
+export type Reminder = {
+  source: Id;
+  task_id: number;
+  content: string;
+  at: string;
+};
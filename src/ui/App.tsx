@@ -10,9 +10,12 @@ import {
   ResizablePanelGroup,
 } from "@/components/ui/resizable";
 import Workspace from "./Workspace";
+import Reminders from "./Reminders";
 import handleKeyboardShortcuts from "@/lib/keyboardShortcuts";
+import handleReminders from "@/lib/reminders";
 
 handleKeyboardShortcuts();
+handleReminders();
 
 function App() {
   const { setTheme } = useTheme();
@@ -44,6 +47,7 @@ function App() {
           <Workspace />
         </ResizablePanel>
       </ResizablePanelGroup>
+      <Reminders />
     </div>
   );
 }
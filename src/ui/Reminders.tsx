@@ -0,0 +1,58 @@
+import { useNoterState } from "@/state/state";
+import { Button } from "@/components/ui/button";
+import { Bell, X } from "lucide-react";
+import { Reminder } from "@/types/reminder";
+
+/** Due reminders in the corner of the window, a task only opens when the user asks for it */
+export default function Reminders() {
+  const [reminders, dismissReminder, openTask] = useNoterState((state) => [
+    state.reminders,
+    state.dismissReminder,
+    state.openTask,
+  ]);
+
+  function handleOpen(reminder: Reminder) {
+    dismissReminder(reminder);
+    openTask(reminder.source, reminder.task_id);
+  }
+
+  if (reminders.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="fixed bottom-4 right-4 z-50 flex flex-col gap-2 w-72">
+      {reminders.map((reminder) => (
+        <div
+          key={`${reminder.source}-${reminder.task_id}`}
+          className="flex items-center gap-2 p-2 rounded-md border bg-background shadow-md"
+        >
+          <Bell className="w-4 h-4 shrink-0" />
+          <div className="flex flex-col grow overflow-hidden">
+            <span className="text-sm font-medium truncate">
+              {reminder.content}
+            </span>
+            <span className="text-xs text-muted-foreground truncate">
+              {reminder.source}
+            </span>
+          </div>
+          <Button
+            size="sm"
+            variant="secondary"
+            onClick={() => handleOpen(reminder)}
+          >
+            Open
+          </Button>
+          <Button
+            size="icon"
+            variant="ghost"
+            className="w-6 h-6 shrink-0"
+            onClick={() => dismissReminder(reminder)}
+          >
+            <X className="w-4 h-4" />
+          </Button>
+        </div>
+      ))}
+    </div>
+  );
+}